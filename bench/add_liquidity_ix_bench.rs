@@ -1,3 +1,4 @@
+use bytemuck::Zeroable;
 use {
     mollusk_svm::Mollusk,
     solana_sdk::{
@@ -9,7 +10,8 @@ use {
     },
 };
 
-use amm_pinocchio::constants::{LP_MINT_SEED, POOL_SEED};
+use amm_pinocchio::constants::{LP_MINT_SEED, POOL_SEED, VAULT_SEED};
+use amm_pinocchio::curve::CURVE_CONSTANT_PRODUCT;
 use amm_pinocchio::states::Pool;
 use mollusk_svm::program;
 use mollusk_svm_bencher::MolluskComputeUnitBencher;
@@ -71,14 +73,27 @@ fn main() {
     .unwrap();
 
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
-        &[POOL_SEED.as_bytes(), token_a.as_ref(), token_b.as_ref()],
+        &[
+            POOL_SEED.as_bytes(),
+            token_a.as_ref(),
+            token_b.as_ref(),
+            &[CURVE_CONSTANT_PRODUCT],
+        ],
         &program_id,
     );
 
     let (lp_mint, lp_mint_bump) =
         Pubkey::find_program_address(&[LP_MINT_SEED.as_bytes(), pool_pda.as_ref()], &program_id);
 
-    let vault_a = Pubkey::new_from_array([0x05; 32]);
+    let (lp_vault, lp_vault_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), lp_mint.as_ref()],
+        &program_id,
+    );
+
+    let (vault_a, vault_a_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_a.as_ref()],
+        &program_id,
+    );
     let mut vault_a_account = Account::new(
         mollusk
             .sysvars
@@ -102,7 +117,10 @@ fn main() {
     )
     .unwrap();
 
-    let vault_b = Pubkey::new_from_array([0x09; 32]);
+    let (vault_b, vault_b_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_b.as_ref()],
+        &program_id,
+    );
     let mut vault_b_account = Account::new(
         mollusk
             .sysvars
@@ -143,7 +161,12 @@ fn main() {
         fee_rate: 30,
         bump: pool_bump,
         lp_mint_bump,
-        _padding: [0; 4],
+        vault_a_bump,
+        vault_b_bump,
+        lp_vault_bump,
+        token_program_a: token_program.to_bytes(),
+        token_program_b: token_program.to_bytes(),
+        ..Zeroable::zeroed()
     };
     pool_account
         .data_as_mut_slice()
@@ -242,6 +265,29 @@ fn main() {
     )
     .unwrap();
 
+    let mut lp_vault_account = Account::new(
+        mollusk
+            .sysvars
+            .rent
+            .minimum_balance(spl_token::state::Account::LEN),
+        spl_token::state::Account::LEN,
+        &token_program,
+    );
+    Pack::pack(
+        spl_token::state::Account {
+            mint: lp_mint,
+            owner: pool_pda,
+            amount: 0,
+            delegate: COption::None,
+            state: spl_token::state::AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        },
+        lp_vault_account.data_as_mut_slice(),
+    )
+    .unwrap();
+
     let (system_program, _system_account) = program::keyed_account_for_system_program();
     let user_account = Account::new(1_000_000_000, 0, &system_program);
 
@@ -266,6 +312,11 @@ fn main() {
             AccountMeta::new(user_token_b, false),
             AccountMeta::new(user_lp_token, false),
             AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(token_a, false),
+            AccountMeta::new_readonly(token_b, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new(lp_vault, false),
         ],
         data,
     };
@@ -281,6 +332,7 @@ fn main() {
         (user_lp_token, user_lp_token_account),
         (token_a, mint_a_account),
         (token_b, mint_b_account),
+        (lp_vault, lp_vault_account),
         (token_program, token_account),
     ];
     MolluskComputeUnitBencher::new(mollusk)
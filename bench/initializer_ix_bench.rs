@@ -1,4 +1,6 @@
-use amm_pinocchio::constants::{LP_MINT_SEED, POOL_SEED};
+use amm_pinocchio::constants::{CONFIG_SEED, LP_MINT_SEED, POOL_SEED, VAULT_SEED};
+use amm_pinocchio::curve::CURVE_CONSTANT_PRODUCT;
+use amm_pinocchio::states::ProtocolConfig;
 use mollusk_svm::Mollusk;
 use mollusk_svm_bencher::MolluskComputeUnitBencher;
 use solana_sdk::{
@@ -63,66 +65,46 @@ fn main() {
     .unwrap();
 
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
-        &[POOL_SEED.as_bytes(), token_a.as_ref(), token_b.as_ref()],
+        &[
+            POOL_SEED.as_bytes(),
+            token_a.as_ref(),
+            token_b.as_ref(),
+            &[CURVE_CONSTANT_PRODUCT],
+        ],
         &program_id,
     );
 
-    let vault_a = Pubkey::new_from_array([0x05; 32]);
-    let mut vault_a_account = Account::new(
-        mollusk
-            .sysvars
-            .rent
-            .minimum_balance(spl_token::state::Account::LEN),
-        spl_token::state::Account::LEN,
-        &token_program,
+    let (vault_a, vault_a_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_a.as_ref()],
+        &program_id,
     );
-    solana_sdk::program_pack::Pack::pack(
-        spl_token::state::Account {
-            mint: token_a,
-            owner: pool_pda,
-            amount: 0,
-            delegate: COption::None,
-            state: spl_token::state::AccountState::Initialized,
-            is_native: COption::None,
-            delegated_amount: 0,
-            close_authority: COption::None,
-        },
-        vault_a_account.data_as_mut_slice(),
-    )
-    .unwrap();
-
-    let vault_b = Pubkey::new_from_array([0x06; 32]);
-    let mut vault_b_account = Account::new(
-        mollusk
-            .sysvars
-            .rent
-            .minimum_balance(spl_token::state::Account::LEN),
-        spl_token::state::Account::LEN,
-        &token_program,
+    let (vault_b, vault_b_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_b.as_ref()],
+        &program_id,
     );
-    solana_sdk::program_pack::Pack::pack(
-        spl_token::state::Account {
-            mint: token_b,
-            owner: pool_pda,
-            amount: 0,
-            delegate: COption::None,
-            state: spl_token::state::AccountState::Initialized,
-            is_native: COption::None,
-            delegated_amount: 0,
-            close_authority: COption::None,
-        },
-        vault_b_account.data_as_mut_slice(),
-    )
-    .unwrap();
 
     let (lp_mint, lp_mint_bump) =
         Pubkey::find_program_address(&[LP_MINT_SEED.as_bytes(), pool_pda.as_ref()], &program_id);
+    let (lp_vault, lp_vault_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), lp_mint.as_ref()],
+        &program_id,
+    );
+    let (config, config_bump) =
+        Pubkey::find_program_address(&[CONFIG_SEED.as_bytes()], &program_id);
 
     let fee_rate: u16 = 30;
+    let flash_loan_fee_rate: u16 = 9;
     let mut data = vec![0u8];
     data.extend_from_slice(&fee_rate.to_le_bytes());
+    data.extend_from_slice(&flash_loan_fee_rate.to_le_bytes());
     data.push(pool_bump);
     data.push(lp_mint_bump);
+    data.push(vault_a_bump);
+    data.push(vault_b_bump);
+    data.push(lp_vault_bump);
+    data.push(6);
+    data.push(CURVE_CONSTANT_PRODUCT);
+    data.extend_from_slice(&0u64.to_le_bytes());
 
     let instruction = Instruction {
         program_id,
@@ -136,6 +118,10 @@ fn main() {
             AccountMeta::new(vault_b, false),
             AccountMeta::new_readonly(system_program, false),
             AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new(lp_vault, false),
+            AccountMeta::new_readonly(config, false),
         ],
         data,
     };
@@ -143,6 +129,22 @@ fn main() {
     let authority_account = Account::new(1_000_000_000, 0, &system_program);
     let pool_account = Account::new(0, 0, &system_program);
     let lp_mint_account = Account::new(0, 0, &system_program);
+    let vault_a_account = Account::new(0, 0, &system_program);
+    let vault_b_account = Account::new(0, 0, &system_program);
+    let lp_vault_account = Account::new(0, 0, &system_program);
+    let mut config_account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(ProtocolConfig::LEN),
+        ProtocolConfig::LEN,
+        &program_id,
+    );
+    config_account
+        .data_as_mut_slice()
+        .copy_from_slice(bytemuck::bytes_of(&ProtocolConfig {
+            authority: Pubkey::new_unique().to_bytes(),
+            protocol_fee_rate: 1_000,
+            bump: config_bump,
+            _padding: [0; 1],
+        }));
     let (_, system_account) = mollusk_svm::program::keyed_account_for_system_program();
     let token_account = mollusk_svm::program::create_program_account_loader_v3(&spl_token::ID);
 
@@ -156,6 +158,8 @@ fn main() {
         (vault_b, vault_b_account),
         (system_program, system_account),
         (token_program, token_account),
+        (lp_vault, lp_vault_account),
+        (config, config_account),
     ];
 
     MolluskComputeUnitBencher::new(mollusk)
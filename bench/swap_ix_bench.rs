@@ -1,7 +1,9 @@
 use amm_pinocchio::{
-    constants::{LP_MINT_SEED, POOL_SEED},
+    constants::{LP_MINT_SEED, POOL_SEED, VAULT_SEED},
+    curve::{CURVE_CONSTANT_PRODUCT, CURVE_STABLE_SWAP},
     states::Pool,
 };
+use bytemuck::Zeroable;
 
 use mollusk_svm::{Mollusk, program};
 use mollusk_svm_bencher::MolluskComputeUnitBencher;
@@ -14,12 +16,14 @@ use solana_sdk::{
 };
 use spl_token::state::Mint;
 
-fn main() {
-    let program_id = Pubkey::new_unique();
-    let mut mollusk = Mollusk::new(&program_id, "tests/elfs/amm_pinocchio");
-
-    mollusk.add_program(&spl_token::ID, "tests/elfs/spl_token-3.5.0");
-
+/// A swap of `data` against a 100_000/100_000 pool on the given curve.
+fn swap_case(
+    mollusk: &Mollusk,
+    program_id: Pubkey,
+    curve_type: u8,
+    amp: u64,
+    data: Vec<u8>,
+) -> (Instruction, Vec<(Pubkey, Account)>) {
     let (token_program, token_account) = (
         spl_token::ID,
         program::create_program_account_loader_v3(&spl_token::ID),
@@ -72,14 +76,22 @@ fn main() {
     .unwrap();
 
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
-        &[POOL_SEED.as_bytes(), token_a.as_ref(), token_b.as_ref()],
+        &[
+            POOL_SEED.as_bytes(),
+            token_a.as_ref(),
+            token_b.as_ref(),
+            &[curve_type],
+        ],
         &program_id,
     );
 
     let (lp_mint, lp_mint_bump) =
         Pubkey::find_program_address(&[LP_MINT_SEED.as_bytes(), pool_pda.as_ref()], &program_id);
 
-    let vault_a = Pubkey::new_from_array([0x05; 32]);
+    let (vault_a, vault_a_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_a.as_ref()],
+        &program_id,
+    );
     let mut vault_a_acc = Account::new(
         mollusk
             .sysvars
@@ -104,7 +116,10 @@ fn main() {
     )
     .unwrap();
 
-    let vault_b = Pubkey::new_from_array([0x09; 32]);
+    let (vault_b, vault_b_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_b.as_ref()],
+        &program_id,
+    );
     let mut vault_b_acc = Account::new(
         mollusk
             .sysvars
@@ -145,7 +160,14 @@ fn main() {
         fee_rate: 30,
         bump: pool_bump,
         lp_mint_bump,
-        _padding: [0; 4],
+        vault_a_bump,
+        vault_b_bump,
+        token_program_a: token_program.to_bytes(),
+        token_program_b: token_program.to_bytes(),
+        curve_type,
+        initial_amp: amp,
+        target_amp: amp,
+        ..Zeroable::zeroed()
     };
     pool_account
         .data_as_mut_slice()
@@ -222,13 +244,6 @@ fn main() {
     let (system_program, _system_account) = program::keyed_account_for_system_program();
     let user_account = Account::new(1_000_000_000, 0, &system_program);
 
-    let amount_in: u64 = 10_000;
-    let min_amount_out: u64 = 9_000;
-
-    let mut data = vec![2u8];
-    data.extend_from_slice(&amount_in.to_le_bytes());
-    data.extend_from_slice(&min_amount_out.to_le_bytes());
-
     let ix = Instruction {
         program_id,
         accounts: vec![
@@ -241,6 +256,7 @@ fn main() {
             AccountMeta::new(user_token_a, false),
             AccountMeta::new(user_token_b, false),
             AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(token_program, false),
         ],
         data,
     };
@@ -257,8 +273,63 @@ fn main() {
         (token_program, token_account),
     ];
 
+    (ix, accounts)
+}
+
+/// Instruction data of `discriminator` followed by two `u64` amounts.
+fn swap_data(discriminator: u8, first: u64, second: u64) -> Vec<u8> {
+    let mut data = vec![discriminator];
+    data.extend_from_slice(&first.to_le_bytes());
+    data.extend_from_slice(&second.to_le_bytes());
+    data
+}
+
+fn main() {
+    let program_id = Pubkey::new_unique();
+    let mut mollusk = Mollusk::new(&program_id, "tests/elfs/amm_pinocchio");
+
+    mollusk.add_program(&spl_token::ID, "tests/elfs/spl_token-3.5.0");
+
+    // Swap: 10_000 in for at least 9_000 out; SwapExactOut: 9_000 out for at
+    // most 10_000 in.
+    let (swap, swap_accounts) = swap_case(
+        &mollusk,
+        program_id,
+        CURVE_CONSTANT_PRODUCT,
+        0,
+        swap_data(2, 10_000, 9_000),
+    );
+    let (swap_exact_out, swap_exact_out_accounts) = swap_case(
+        &mollusk,
+        program_id,
+        CURVE_CONSTANT_PRODUCT,
+        0,
+        swap_data(5, 9_000, 10_000),
+    );
+    let (stable_swap, stable_swap_accounts) = swap_case(
+        &mollusk,
+        program_id,
+        CURVE_STABLE_SWAP,
+        100,
+        swap_data(2, 10_000, 9_000),
+    );
+    let (stable_swap_exact_out, stable_swap_exact_out_accounts) = swap_case(
+        &mollusk,
+        program_id,
+        CURVE_STABLE_SWAP,
+        100,
+        swap_data(5, 9_000, 10_000),
+    );
+
     MolluskComputeUnitBencher::new(mollusk)
-        .bench(("swap", &ix, &accounts))
+        .bench(("swap", &swap, &swap_accounts))
+        .bench(("swap_exact_out", &swap_exact_out, &swap_exact_out_accounts))
+        .bench(("stable_swap", &stable_swap, &stable_swap_accounts))
+        .bench((
+            "stable_swap_exact_out",
+            &stable_swap_exact_out,
+            &stable_swap_exact_out_accounts,
+        ))
         .must_pass(true)
         .out_dir("target/benches")
         .execute();
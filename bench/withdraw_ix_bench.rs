@@ -1,7 +1,9 @@
 use amm_pinocchio::{
-    constants::{LP_MINT_SEED, POOL_SEED},
+    constants::{LP_MINT_SEED, POOL_SEED, VAULT_SEED},
+    curve::CURVE_CONSTANT_PRODUCT,
     states::Pool,
 };
+use bytemuck::Zeroable;
 use mollusk_svm::{Mollusk, program};
 use mollusk_svm_bencher::MolluskComputeUnitBencher;
 use solana_sdk::{
@@ -69,14 +71,22 @@ fn main() {
     )
     .unwrap();
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
-        &[POOL_SEED.as_bytes(), token_a.as_ref(), token_b.as_ref()],
+        &[
+            POOL_SEED.as_bytes(),
+            token_a.as_ref(),
+            token_b.as_ref(),
+            &[CURVE_CONSTANT_PRODUCT],
+        ],
         &program_id,
     );
 
     let (lp_mint, lp_mint_bump) =
         Pubkey::find_program_address(&[LP_MINT_SEED.as_bytes(), pool_pda.as_ref()], &program_id);
 
-    let vault_a = Pubkey::new_from_array([0x05; 32]);
+    let (vault_a, vault_a_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_a.as_ref()],
+        &program_id,
+    );
     let mut vault_a_acc = Account::new(
         mollusk
             .sysvars
@@ -101,7 +111,10 @@ fn main() {
     )
     .unwrap();
 
-    let vault_b = Pubkey::new_from_array([0x09; 32]);
+    let (vault_b, vault_b_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_b.as_ref()],
+        &program_id,
+    );
     let mut vault_b_acc = Account::new(
         mollusk
             .sysvars
@@ -141,7 +154,11 @@ fn main() {
         fee_rate: 30,
         bump: pool_bump,
         lp_mint_bump,
-        _padding: [0; 4],
+        vault_a_bump,
+        vault_b_bump,
+        token_program_a: token_program.to_bytes(),
+        token_program_b: token_program.to_bytes(),
+        ..Zeroable::zeroed()
     };
     pool_account
         .data_as_mut_slice()
@@ -263,6 +280,10 @@ fn main() {
             solana_sdk::instruction::AccountMeta::new(user_token_a, false),
             solana_sdk::instruction::AccountMeta::new(user_token_b, false),
             solana_sdk::instruction::AccountMeta::new_readonly(token_program, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(token_a, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(token_b, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(token_program, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(token_program, false),
         ],
         data,
     };
@@ -276,6 +297,8 @@ fn main() {
         (user_lp_token, user_lp_token_account),
         (user_token_a, user_token_a_account),
         (user_token_b, user_token_b_account),
+        (token_a, mint_a_account),
+        (token_b, mint_b_account),
         (token_program, token_account),
     ];
 
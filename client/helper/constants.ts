@@ -8,4 +8,8 @@ export const POOL_SEED = "pool";
 
 export const LP_MINT_SEED = "lp_mint";
 
+export const VAULT_SEED = "vault";
+
+export const CONFIG_SEED = "config";
+
 export const RPC_URL = "https://api.devnet.solana.com";
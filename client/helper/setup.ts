@@ -1,32 +1,30 @@
+import { Connection, Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, createAccount, mintTo } from "@solana/spl-token";
 import {
-  Connection,
-  Keypair,
-  PublicKey,
-  sendAndConfirmTransaction,
-  SystemProgram,
-  Transaction,
-} from "@solana/web3.js";
-import {
-  createMint,
-  createAccount,
-  mintTo,
-  ACCOUNT_SIZE,
-  createInitializeAccountInstruction,
-  TOKEN_PROGRAM_ID,
-} from "@solana/spl-token";
-import { LP_MINT_SEED, POOL_SEED, PROGRAM_ID } from "./constants";
+  CONFIG_SEED,
+  LP_MINT_SEED,
+  POOL_SEED,
+  PROGRAM_ID,
+  VAULT_SEED,
+} from "./constants";
 import { SetupResult } from "./types";
 
 export async function setupPoolAccounts(
   connection: Connection,
   payer: Keypair,
   _programId: PublicKey,
+  curveType = 0,
 ): Promise<SetupResult> {
   const mintA = await createMint(connection, payer, payer.publicKey, null, 6);
   const mintB = await createMint(connection, payer, payer.publicKey, null, 6);
 
   const [poolPda, poolBump] = PublicKey.findProgramAddressSync(
-    [Buffer.from(POOL_SEED), mintA.toBuffer(), mintB.toBuffer()],
+    [
+      Buffer.from(POOL_SEED),
+      mintA.toBuffer(),
+      mintB.toBuffer(),
+      Buffer.from([curveType]),
+    ],
     PROGRAM_ID,
   );
 
@@ -35,54 +33,25 @@ export async function setupPoolAccounts(
     PROGRAM_ID,
   );
 
-  const vaultAKeypair = Keypair.generate();
-  const vaultBKeypair = Keypair.generate();
-
-  const vaultA = vaultAKeypair.publicKey;
-  const vaultB = vaultBKeypair.publicKey;
+  const [vaultA, vaultABump] = PublicKey.findProgramAddressSync(
+    [Buffer.from(VAULT_SEED), poolPda.toBuffer(), mintA.toBuffer()],
+    PROGRAM_ID,
+  );
 
-  const lamports =
-    await connection.getMinimumBalanceForRentExemption(ACCOUNT_SIZE);
+  const [vaultB, vaultBBump] = PublicKey.findProgramAddressSync(
+    [Buffer.from(VAULT_SEED), poolPda.toBuffer(), mintB.toBuffer()],
+    PROGRAM_ID,
+  );
 
-  const createVaultATx = new Transaction().add(
-    SystemProgram.createAccount({
-      fromPubkey: payer.publicKey,
-      newAccountPubkey: vaultA,
-      space: ACCOUNT_SIZE,
-      lamports,
-      programId: TOKEN_PROGRAM_ID,
-    }),
-    createInitializeAccountInstruction(
-      vaultA,
-      mintA,
-      poolPda,
-      TOKEN_PROGRAM_ID,
-    ),
+  const [lpVault, lpVaultBump] = PublicKey.findProgramAddressSync(
+    [Buffer.from(VAULT_SEED), poolPda.toBuffer(), lpMint.toBuffer()],
+    PROGRAM_ID,
   );
-  await sendAndConfirmTransaction(connection, createVaultATx, [
-    payer,
-    vaultAKeypair,
-  ]);
 
-  const createVaultBTx = new Transaction().add(
-    SystemProgram.createAccount({
-      fromPubkey: payer.publicKey,
-      newAccountPubkey: vaultB,
-      space: ACCOUNT_SIZE,
-      lamports,
-      programId: TOKEN_PROGRAM_ID,
-    }),
-    createInitializeAccountInstruction(
-      vaultB,
-      mintB,
-      poolPda,
-      TOKEN_PROGRAM_ID,
-    ),
+  const [config] = PublicKey.findProgramAddressSync(
+    [Buffer.from(CONFIG_SEED)],
+    PROGRAM_ID,
   );
-  await sendAndConfirmTransaction(connection, createVaultBTx, [
-    payer,
-    vaultBKeypair,
-  ]);
 
   const userTokenA = await createAccount(
     connection,
@@ -107,9 +76,14 @@ export async function setupPoolAccounts(
     lpMint,
     vaultA,
     vaultB,
+    lpVault,
+    config,
     userTokenA,
     userTokenB,
     poolBump,
     lpMintBump,
+    vaultABump,
+    vaultBBump,
+    lpVaultBump,
   };
 }
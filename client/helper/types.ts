@@ -7,24 +7,32 @@ export interface SetupResult {
   lpMint: PublicKey;
   vaultA: PublicKey;
   vaultB: PublicKey;
+  lpVault: PublicKey;
+  config: PublicKey;
   userTokenA: PublicKey;
   userTokenB: PublicKey;
   poolBump: number;
   lpMintBump: number;
+  vaultABump: number;
+  vaultBBump: number;
+  lpVaultBump: number;
 }
 
 export interface AddLiquidityParams {
   programId: PublicKey;
   payer: PublicKey;
   poolPda: PublicKey;
+  mintA: PublicKey;
+  mintB: PublicKey;
   lpMint: PublicKey;
   vaultA: PublicKey;
   vaultB: PublicKey;
   userTokenA: PublicKey;
   userTokenB: PublicKey;
   userLpToken: PublicKey;
-  amountA: bigint;
-  amountB: bigint;
+  lpVault: PublicKey;
+  maxAmountA: bigint;
+  maxAmountB: bigint;
   minLpAmount: bigint;
 }
 
@@ -37,9 +45,18 @@ export interface InitializeParams {
   lpMint: PublicKey;
   vaultA: PublicKey;
   vaultB: PublicKey;
+  lpVault: PublicKey;
+  config: PublicKey;
   feeRate: number;
+  flashLoanFeeRate: number;
   poolBump: number;
   lpMintBump: number;
+  vaultABump: number;
+  vaultBBump: number;
+  lpVaultBump: number;
+  lpDecimals: number;
+  curveType: number;
+  amp: bigint;
 }
 
 export interface SwapParams {
@@ -60,6 +77,8 @@ export interface WithdrawParams {
   programId: PublicKey;
   payer: PublicKey;
   poolPda: PublicKey;
+  mintA: PublicKey;
+  mintB: PublicKey;
   lpMint: PublicKey;
   vaultA: PublicKey;
   vaultB: PublicKey;
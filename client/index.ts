@@ -31,8 +31,15 @@ async function main() {
     payer: payer.publicKey,
     ...setup,
     feeRate: 30,
+    flashLoanFeeRate: 9,
     poolBump: setup.poolBump,
     lpMintBump: setup.lpMintBump,
+    vaultABump: setup.vaultABump,
+    vaultBBump: setup.vaultBBump,
+    lpVaultBump: setup.lpVaultBump,
+    lpDecimals: 6,
+    curveType: 0,
+    amp: 0n,
   });
 
   const initTx = new Transaction().add(initIx);
@@ -52,8 +59,8 @@ async function main() {
     payer: payer.publicKey,
     ...setup,
     userLpToken,
-    amountA: BigInt(100_000),
-    amountB: BigInt(100_000),
+    maxAmountA: BigInt(100_000),
+    maxAmountB: BigInt(100_000),
     minLpAmount: BigInt(0),
   });
 
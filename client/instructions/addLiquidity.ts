@@ -7,8 +7,8 @@ export function createAddLiquidityInstruction(
 ): TransactionInstruction {
   const data = Buffer.alloc(25);
   data.writeUInt8(1, 0);
-  data.writeBigUInt64LE(params.amountA, 1);
-  data.writeBigUInt64LE(params.amountB, 9);
+  data.writeBigUInt64LE(params.maxAmountA, 1);
+  data.writeBigUInt64LE(params.maxAmountB, 9);
   data.writeBigUInt64LE(params.minLpAmount, 17);
 
   return new TransactionInstruction({
@@ -23,6 +23,11 @@ export function createAddLiquidityInstruction(
       { pubkey: params.userTokenB, isSigner: false, isWritable: true },
       { pubkey: params.userLpToken, isSigner: false, isWritable: true },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: params.mintA, isSigner: false, isWritable: false },
+      { pubkey: params.mintB, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: params.lpVault, isSigner: false, isWritable: true },
     ],
     data,
   });
@@ -5,11 +5,18 @@ import { InitializeParams } from "../helper/types";
 export function createInitializeInstruction(
   params: InitializeParams,
 ): TransactionInstruction {
-  const initData = Buffer.alloc(5);
+  const initData = Buffer.alloc(20);
   initData.writeUInt8(0, 0);
   initData.writeUInt16LE(params.feeRate, 1);
-  initData.writeUInt8(params.poolBump, 3);
-  initData.writeUInt8(params.lpMintBump, 4);
+  initData.writeUInt16LE(params.flashLoanFeeRate, 3);
+  initData.writeUInt8(params.poolBump, 5);
+  initData.writeUInt8(params.lpMintBump, 6);
+  initData.writeUInt8(params.vaultABump, 7);
+  initData.writeUInt8(params.vaultBBump, 8);
+  initData.writeUInt8(params.lpVaultBump, 9);
+  initData.writeUInt8(params.lpDecimals, 10);
+  initData.writeUInt8(params.curveType, 11);
+  initData.writeBigUInt64LE(params.amp, 12);
 
   return new TransactionInstruction({
     programId: params.programId,
@@ -23,6 +30,10 @@ export function createInitializeInstruction(
       { pubkey: params.vaultB, isSigner: false, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: params.lpVault, isSigner: false, isWritable: true },
+      { pubkey: params.config, isSigner: false, isWritable: false },
       { pubkey: params.payer, isSigner: true, isWritable: true },
       { pubkey: params.poolPda, isSigner: false, isWritable: true },
     ],
@@ -22,6 +22,7 @@ export function createSwapInstruction(
       { pubkey: params.userTokenA, isSigner: false, isWritable: true },
       { pubkey: params.userTokenB, isSigner: false, isWritable: true },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
     ],
     data,
   });
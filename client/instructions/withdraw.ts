@@ -23,6 +23,10 @@ export function createWithdrawInstruction(
       { pubkey: params.userTokenA, isSigner: false, isWritable: true },
       { pubkey: params.userTokenB, isSigner: false, isWritable: true },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: params.mintA, isSigner: false, isWritable: false },
+      { pubkey: params.mintB, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
     ],
     data,
   });
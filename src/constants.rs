@@ -2,6 +2,53 @@ pub const SYSTEM_PROGRAM_ID: [u8; 32] = [
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
 ];
 
+/// The upgradeable BPF loader, which owns the `ProgramData` account recording
+/// the program's upgrade authority.
+pub const BPF_LOADER_UPGRADEABLE_ID: [u8; 32] = [
+    2, 168, 246, 145, 78, 136, 161, 176, 226, 16, 21, 62, 247, 99, 174, 43, 0, 194, 185, 61, 22,
+    193, 36, 210, 192, 83, 122, 16, 4, 128, 0, 0,
+];
+
 pub const POOL_SEED: &str = "pool";
 
+pub const CONFIG_SEED: &str = "config";
+
 pub const LP_MINT_SEED: &str = "lp_mint";
+
+pub const VAULT_SEED: &str = "vault";
+
+/// Largest LP mint decimals accepted by `Initialize`, matching native SOL.
+pub const MAX_LP_DECIMALS: u8 = 9;
+
+/// LP tokens minted to the pool's LP vault on the first deposit. They can never
+/// be withdrawn, so the LP supply cannot be driven back to zero or to dust.
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+/// `Pool::status` bit that disables swaps, including routed and single-sided
+/// ones, which trade against the pool, and flash borrows.
+pub const SWAPS_DISABLED: u8 = 1 << 0;
+
+/// `Pool::status` bit that disables deposits.
+pub const DEPOSITS_DISABLED: u8 = 1 << 1;
+
+/// `Pool::status` bit that disables withdrawals.
+pub const WITHDRAWALS_DISABLED: u8 = 1 << 2;
+
+/// Every valid `Pool::status` bit.
+pub const POOL_STATUS_MASK: u8 = SWAPS_DISABLED | DEPOSITS_DISABLED | WITHDRAWALS_DISABLED;
+
+/// Smallest amplification coefficient of a StableSwap pool.
+pub const MIN_AMP: u64 = 1;
+
+/// Largest amplification coefficient of a StableSwap pool.
+pub const MAX_AMP: u64 = 1_000_000;
+
+/// Largest factor by which one `RampAmp` may raise or lower the amplification
+/// coefficient.
+pub const MAX_AMP_CHANGE: u64 = 10;
+
+/// Shortest `RampAmp`, in seconds, so LPs have time to react to it.
+pub const MIN_RAMP_DURATION: i64 = 86_400;
+
+/// Maximum number of transfer hook accounts forwarded with a token transfer.
+pub const MAX_TRANSFER_HOOK_ACCOUNTS: usize = 20;
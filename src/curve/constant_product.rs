@@ -0,0 +1,60 @@
+use pinocchio::program_error::ProgramError;
+
+use super::{SwapCurve, proportional_deposit};
+use crate::{
+    error::AmmError,
+    helper::{calculate_swap_input, calculate_swap_output},
+    math::integer_sqrt,
+};
+
+/// The Uniswap V2 invariant `reserve_a * reserve_b = k`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConstantProduct;
+
+impl SwapCurve for ConstantProduct {
+    fn swap_out(
+        &self,
+        amount_in: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        fee_rate: u16,
+    ) -> Result<(u64, u64), ProgramError> {
+        calculate_swap_output(amount_in, reserve_in, reserve_out, fee_rate)
+    }
+
+    fn swap_in(
+        &self,
+        amount_out: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        fee_rate: u16,
+    ) -> Result<(u64, u64), ProgramError> {
+        calculate_swap_input(amount_out, reserve_in, reserve_out, fee_rate)
+    }
+
+    /// `sqrt(amount_a * amount_b)` for the first deposit, otherwise the smaller of
+    /// the two proportional shares.
+    fn deposit(
+        &self,
+        amount_a: u64,
+        amount_b: u64,
+        reserve_a: u64,
+        reserve_b: u64,
+        lp_supply: u64,
+    ) -> Result<u64, ProgramError> {
+        if lp_supply == 0 {
+            // The root of a product of two `u64` values always fits in a `u64`.
+            return Ok(integer_sqrt(amount_a as u128 * amount_b as u128) as u64);
+        }
+
+        proportional_deposit(amount_a, amount_b, reserve_a, reserve_b, lp_supply)
+    }
+
+    /// `reserve_quote / reserve_base`.
+    fn spot_price(&self, reserve_base: u64, reserve_quote: u64) -> Result<u128, ProgramError> {
+        if reserve_base == 0 {
+            return Err(AmmError::EmptyPool.into());
+        }
+        Ok(((reserve_quote as u128) << 64) / reserve_base as u128)
+    }
+}
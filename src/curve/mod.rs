@@ -0,0 +1,179 @@
+//! Invariants a pool can trade on, selected by `Pool::curve_type`.
+
+mod constant_product;
+mod stable_swap;
+
+use pinocchio::program_error::ProgramError;
+
+pub use constant_product::ConstantProduct;
+pub use stable_swap::StableSwap;
+
+use crate::{
+    constants::{MAX_AMP, MIN_AMP},
+    error::AmmError,
+    math::mul_div_floor,
+};
+
+/// `Pool::curve_type` of a constant-product (`x * y = k`) pool.
+pub const CURVE_CONSTANT_PRODUCT: u8 = 0;
+
+/// `Pool::curve_type` of a StableSwap pool, priced by its amplification
+/// coefficient.
+pub const CURVE_STABLE_SWAP: u8 = 1;
+
+/// Pricing of swaps and liquidity for one invariant.
+///
+/// Every method rounds in the pool's favour. Reserves are the amounts the pool
+/// trades on, so they exclude protocol fees.
+pub trait SwapCurve {
+    /// Output for `amount_in`, returning `(amount_out, fee)`.
+    ///
+    /// The fee is the part of `amount_in` that is not counted towards the
+    /// invariant; it stays in the pool.
+    fn swap_out(
+        &self,
+        amount_in: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        fee_rate: u16,
+    ) -> Result<(u64, u64), ProgramError>;
+
+    /// Smallest input whose output covers `amount_out`, returning
+    /// `(amount_in, fee)`.
+    fn swap_in(
+        &self,
+        amount_out: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        fee_rate: u16,
+    ) -> Result<(u64, u64), ProgramError>;
+
+    /// LP tokens minted for depositing `amount_a` and `amount_b`.
+    ///
+    /// On a pool with no LP supply this is the initial liquidity, before
+    /// `MINIMUM_LIQUIDITY` is locked out of it. Whatever the reserves hold
+    /// then, e.g. donations, is not counted towards it.
+    fn deposit(
+        &self,
+        amount_a: u64,
+        amount_b: u64,
+        reserve_a: u64,
+        reserve_b: u64,
+        lp_supply: u64,
+    ) -> Result<u64, ProgramError>;
+
+    /// Marginal price of the base token in the quote token at non-empty
+    /// reserves, as a Q64.64 value.
+    fn spot_price(&self, reserve_base: u64, reserve_quote: u64) -> Result<u128, ProgramError>;
+
+    /// Amounts paid out for burning `lp_amount`, returning
+    /// `(amount_a, amount_b)`.
+    ///
+    /// Defaults to the proportional share of each reserve, rounded down.
+    fn withdraw(
+        &self,
+        lp_amount: u64,
+        reserve_a: u64,
+        reserve_b: u64,
+        lp_supply: u64,
+    ) -> Result<(u64, u64), ProgramError> {
+        Ok((
+            mul_div_floor(lp_amount, reserve_a, lp_supply)?,
+            mul_div_floor(lp_amount, reserve_b, lp_supply)?,
+        ))
+    }
+}
+
+/// The smaller of the proportional shares of `lp_supply` that `amount_a` and
+/// `amount_b` buy, rounded down.
+fn proportional_deposit(
+    amount_a: u64,
+    amount_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> Result<u64, ProgramError> {
+    let a = mul_div_floor(amount_a, lp_supply, reserve_a)?;
+    let b = mul_div_floor(amount_b, lp_supply, reserve_b)?;
+    Ok(a.min(b))
+}
+
+/// The curve of a pool, dispatched on `Pool::curve_type`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Curve {
+    ConstantProduct(ConstantProduct),
+    StableSwap(StableSwap),
+}
+
+impl Curve {
+    /// `amp` must be zero for a constant-product pool and within
+    /// `MIN_AMP..=MAX_AMP` for a StableSwap pool.
+    pub fn new(curve_type: u8, amp: u64) -> Result<Self, ProgramError> {
+        match curve_type {
+            CURVE_CONSTANT_PRODUCT if amp == 0 => Ok(Self::ConstantProduct(ConstantProduct)),
+            CURVE_STABLE_SWAP if (MIN_AMP..=MAX_AMP).contains(&amp) => {
+                Ok(Self::StableSwap(StableSwap { amp }))
+            }
+            CURVE_CONSTANT_PRODUCT | CURVE_STABLE_SWAP => Err(AmmError::InvalidAmp.into()),
+            _ => Err(AmmError::InvalidCurveType.into()),
+        }
+    }
+
+    fn inner(&self) -> &dyn SwapCurve {
+        match self {
+            Self::ConstantProduct(curve) => curve,
+            Self::StableSwap(curve) => curve,
+        }
+    }
+}
+
+impl SwapCurve for Curve {
+    fn swap_out(
+        &self,
+        amount_in: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        fee_rate: u16,
+    ) -> Result<(u64, u64), ProgramError> {
+        self.inner()
+            .swap_out(amount_in, reserve_in, reserve_out, fee_rate)
+    }
+
+    fn swap_in(
+        &self,
+        amount_out: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        fee_rate: u16,
+    ) -> Result<(u64, u64), ProgramError> {
+        self.inner()
+            .swap_in(amount_out, reserve_in, reserve_out, fee_rate)
+    }
+
+    fn deposit(
+        &self,
+        amount_a: u64,
+        amount_b: u64,
+        reserve_a: u64,
+        reserve_b: u64,
+        lp_supply: u64,
+    ) -> Result<u64, ProgramError> {
+        self.inner()
+            .deposit(amount_a, amount_b, reserve_a, reserve_b, lp_supply)
+    }
+
+    fn spot_price(&self, reserve_base: u64, reserve_quote: u64) -> Result<u128, ProgramError> {
+        self.inner().spot_price(reserve_base, reserve_quote)
+    }
+
+    fn withdraw(
+        &self,
+        lp_amount: u64,
+        reserve_a: u64,
+        reserve_b: u64,
+        lp_supply: u64,
+    ) -> Result<(u64, u64), ProgramError> {
+        self.inner()
+            .withdraw(lp_amount, reserve_a, reserve_b, lp_supply)
+    }
+}
@@ -0,0 +1,249 @@
+use pinocchio::program_error::ProgramError;
+
+use super::{SwapCurve, proportional_deposit};
+use crate::{
+    error::AmmError,
+    math::{mul_add_div_wide, mul_div_ceil, mul_div_floor, mul_div_wide},
+};
+
+/// Number of tokens in a pool.
+const N_COINS: u128 = 2;
+
+/// Newton steps after which a calculation that has not converged fails.
+const MAX_ITERATIONS: usize = 32;
+
+/// The Curve StableSwap invariant for two tokens,
+/// `A * n^n * (x + y) + D = A * n^n * D + D^(n + 1) / (n^n * x * y)` with
+/// `n = 2`.
+///
+/// It trades close to 1:1 around the balanced point, with less slippage the
+/// larger the amplification coefficient `amp` (`A`), and falls back to
+/// constant-product behaviour as the pool becomes imbalanced. Both tokens are
+/// priced in base units, so it assumes a 1:1 peg between mints of equal
+/// decimals.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StableSwap {
+    pub amp: u64,
+}
+
+impl StableSwap {
+    /// `A * n^n`.
+    fn ann(&self) -> u128 {
+        self.amp as u128 * N_COINS * N_COINS
+    }
+
+    /// The invariant `D` of reserves `x` and `y`, by Newton's method starting
+    /// from `x + y`.
+    pub fn compute_d(&self, x: u64, y: u64) -> Result<u128, ProgramError> {
+        if x == 0 && y == 0 {
+            return Ok(0);
+        }
+        if x == 0 || y == 0 {
+            return Err(AmmError::EmptyPool.into());
+        }
+
+        let (x, y) = (x as u128, y as u128);
+        let sum = x + y;
+        let ann = self.ann();
+
+        let mut d = sum;
+        for _ in 0..MAX_ITERATIONS {
+            // D^(n + 1) / (n^n * x * y)
+            let d_p = mul_div_wide(d, d, x * N_COINS)?;
+            let d_p = mul_div_wide(d_p, d, y * N_COINS)?;
+
+            // D = (Ann * S + n * D_P) * D / ((Ann - 1) * D + (n + 1) * D_P)
+            let numerator = d_p
+                .checked_mul(N_COINS)
+                .and_then(|value| value.checked_add(ann * sum))
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            let denominator = d_p
+                .checked_mul(N_COINS + 1)
+                .and_then(|value| value.checked_add(ann.saturating_sub(1).checked_mul(d)?))
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+
+            let previous = d;
+            d = mul_div_wide(numerator, d, denominator)?;
+            if d.abs_diff(previous) <= 1 {
+                return Ok(d);
+            }
+        }
+
+        Err(AmmError::CurveNotConverged.into())
+    }
+
+    /// The reserve of one token that keeps the invariant at `d` when the other
+    /// reserve is `x`, by Newton's method starting from `d`.
+    fn compute_y(&self, x: u128, d: u128) -> Result<u128, ProgramError> {
+        if x == 0 {
+            return Err(AmmError::InsufficientLiquidity.into());
+        }
+
+        let ann = self.ann();
+
+        // c = D^(n + 1) / (n^n * x * Ann), b = x + D / Ann
+        let c = mul_div_wide(d, d, x * N_COINS)?;
+        let c = mul_div_wide(c, d, ann * N_COINS)?;
+        let b = x + d / ann;
+
+        let mut y = d;
+        for _ in 0..MAX_ITERATIONS {
+            // y = (y^2 + c) / (2 * y + b - D), where `y^2` outgrows a `u128`
+            // once `D` passes 2^64.
+            let denominator = y
+                .checked_mul(2)
+                .and_then(|value| value.checked_add(b))
+                .and_then(|value| value.checked_sub(d))
+                .filter(|denominator| *denominator > 0)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+
+            let previous = y;
+            y = mul_add_div_wide(y, y, c, denominator)?;
+            if y.abs_diff(previous) <= 1 {
+                return Ok(y);
+            }
+        }
+
+        Err(AmmError::CurveNotConverged.into())
+    }
+
+    /// `swap_out` against a precomputed invariant `d` of the reserves.
+    fn swap_out_with_d(
+        &self,
+        amount_in: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        fee_rate: u16,
+        d: u128,
+    ) -> Result<(u64, u64), ProgramError> {
+        let fee_multiplier = 10000_u64
+            .checked_sub(fee_rate as u64)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let amount_in_with_fee = mul_div_floor(amount_in, fee_multiplier, 10000)?;
+
+        let new_reserve_out = self.compute_y(reserve_in as u128 + amount_in_with_fee as u128, d)?;
+
+        // Newton's method stops within a unit of the exact reserve, so one
+        // more unit is held back.
+        let amount_out = (reserve_out as u128)
+            .saturating_sub(new_reserve_out)
+            .saturating_sub(1);
+
+        Ok((amount_out as u64, amount_in - amount_in_with_fee))
+    }
+}
+
+impl SwapCurve for StableSwap {
+    fn swap_out(
+        &self,
+        amount_in: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        fee_rate: u16,
+    ) -> Result<(u64, u64), ProgramError> {
+        let d = self.compute_d(reserve_in, reserve_out)?;
+        self.swap_out_with_d(amount_in, reserve_in, reserve_out, fee_rate, d)
+    }
+
+    /// Inverts the curve for an estimate, which Newton's rounding can leave a
+    /// few units off, then searches around it for the smallest input that
+    /// `swap_out` prices at `amount_out` or more.
+    fn swap_in(
+        &self,
+        amount_out: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        fee_rate: u16,
+    ) -> Result<(u64, u64), ProgramError> {
+        // `swap_out` holds back one unit, so the last one can never be bought.
+        if amount_out >= reserve_out.saturating_sub(1) {
+            return Err(AmmError::InsufficientLiquidity.into());
+        }
+
+        let fee_multiplier = 10000_u64
+            .checked_sub(fee_rate as u64)
+            .filter(|multiplier| *multiplier > 0)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let d = self.compute_d(reserve_in, reserve_out)?;
+        let covers = |amount_in: u64| -> Result<bool, ProgramError> {
+            let (paid, _) =
+                self.swap_out_with_d(amount_in, reserve_in, reserve_out, fee_rate, d)?;
+            Ok(paid >= amount_out)
+        };
+
+        let new_reserve_in = self.compute_y((reserve_out - amount_out - 1) as u128, d)?;
+        let amount_in_with_fee = u64::try_from(new_reserve_in.saturating_sub(reserve_in as u128))
+            .map_err(|_| ProgramError::ArithmeticOverflow)?;
+        let estimate = mul_div_ceil(amount_in_with_fee, 10000, fee_multiplier)?;
+
+        // Widen `(low, high]` around the estimate until `high` covers the
+        // output and `low` does not, then bisect it.
+        let (mut low, mut high) = (estimate.saturating_sub(1), estimate.max(1));
+        let mut step = 1_u64;
+        while !covers(high)? {
+            low = high;
+            high = high
+                .checked_add(step)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            step = step.saturating_mul(2);
+        }
+        step = 1;
+        while low > 0 && covers(low)? {
+            high = low;
+            low = low.saturating_sub(step);
+            step = step.saturating_mul(2);
+        }
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if covers(middle)? {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+
+        let amount_in_with_fee = mul_div_floor(high, fee_multiplier, 10000)?;
+        Ok((high, high - amount_in_with_fee))
+    }
+
+    /// The invariant `D` of the amounts for the first deposit, otherwise the smaller of the two
+    /// proportional shares.
+    ///
+    /// `D` scales linearly with the reserves, so for the ratio-matched deposits
+    /// of `AddLiquidity` the proportional share equals the growth of `D`, and
+    /// an imbalanced deposit cannot mint more than its balanced part is worth.
+    fn deposit(
+        &self,
+        amount_a: u64,
+        amount_b: u64,
+        reserve_a: u64,
+        reserve_b: u64,
+        lp_supply: u64,
+    ) -> Result<u64, ProgramError> {
+        if lp_supply == 0 {
+            let d = self.compute_d(amount_a, amount_b)?;
+            return u64::try_from(d).map_err(|_| ProgramError::ArithmeticOverflow);
+        }
+
+        proportional_deposit(amount_a, amount_b, reserve_a, reserve_b, lp_supply)
+    }
+
+    /// `-dy/dx` of the invariant with `x = reserve_base`, `y = reserve_quote`:
+    ///
+    /// `y * (t * x + D) / (x * (t * y + D))` with `t = 4 * Ann * x * y / D^2`.
+    ///
+    /// It lies between the reserve ratio `y / x` and 1, so the intermediate
+    /// `y * (t * x + D) / (t * y + D)` stays below `max(x, y) * 2^64`.
+    fn spot_price(&self, reserve_base: u64, reserve_quote: u64) -> Result<u128, ProgramError> {
+        let d = self.compute_d(reserve_base, reserve_quote)?;
+        let (x, y) = (reserve_base as u128, reserve_quote as u128);
+
+        // 4 * Ann * x * y / D
+        let u = mul_div_wide(4 * self.ann() * x, y, d)?;
+        let base_term = mul_div_wide(u, x, d)? + d;
+        let quote_term = mul_div_wide(u, y, d)? + d;
+
+        Ok(mul_div_wide(y << 64, base_term, quote_term)? / x)
+    }
+}
@@ -6,8 +6,27 @@ use pinocchio::{
 };
 
 use crate::instructions::{
-    add_liquidity::process_add_liquidity, initializer::process_initialize, swap::process_swap,
+    add_liquidity::process_add_liquidity,
+    admin::{
+        process_accept_authority, process_propose_authority, process_ramp_amp,
+        process_set_pool_status, process_update_pool,
+    },
+    close_pool::process_close_pool,
+    collect_protocol_fees::process_collect_protocol_fees,
+    deposit_single::process_deposit_single,
+    flash_loan::{process_flash_borrow, process_flash_repay},
+    initializer::process_initialize,
+    observe::process_observe,
+    protocol_config::{
+        process_initialize_config, process_set_protocol_fee_rate, process_update_config,
+    },
+    quote_swap::process_quote_swap,
+    route_swap::process_route_swap,
+    swap::process_swap,
+    swap_exact_out::process_swap_exact_out,
+    sync_skim::{process_skim, process_sync},
     withdraw::process_withdraw,
+    withdraw_single::process_withdraw_single,
 };
 
 program_entrypoint!(process_instruction);
@@ -26,6 +45,26 @@ fn process_instruction(
         Some((1, rest)) => process_add_liquidity(program_id, accounts, rest),
         Some((2, rest)) => process_swap(program_id, accounts, rest),
         Some((3, rest)) => process_withdraw(program_id, accounts, rest),
+        Some((4, rest)) => process_quote_swap(program_id, accounts, rest),
+        Some((5, rest)) => process_swap_exact_out(program_id, accounts, rest),
+        Some((6, rest)) => process_route_swap(program_id, accounts, rest),
+        Some((7, rest)) => process_collect_protocol_fees(program_id, accounts, rest),
+        Some((8, rest)) => process_observe(program_id, accounts, rest),
+        Some((9, rest)) => process_flash_borrow(program_id, accounts, rest),
+        Some((10, rest)) => process_flash_repay(program_id, accounts, rest),
+        Some((11, rest)) => process_sync(program_id, accounts, rest),
+        Some((12, rest)) => process_skim(program_id, accounts, rest),
+        Some((13, rest)) => process_deposit_single(program_id, accounts, rest),
+        Some((14, rest)) => process_withdraw_single(program_id, accounts, rest),
+        Some((15, rest)) => process_update_pool(program_id, accounts, rest),
+        Some((16, rest)) => process_propose_authority(program_id, accounts, rest),
+        Some((17, rest)) => process_accept_authority(program_id, accounts, rest),
+        Some((18, rest)) => process_set_pool_status(program_id, accounts, rest),
+        Some((19, rest)) => process_close_pool(program_id, accounts, rest),
+        Some((20, rest)) => process_ramp_amp(program_id, accounts, rest),
+        Some((21, rest)) => process_initialize_config(program_id, accounts, rest),
+        Some((22, rest)) => process_update_config(program_id, accounts, rest),
+        Some((23, rest)) => process_set_protocol_fee_rate(program_id, accounts, rest),
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
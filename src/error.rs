@@ -0,0 +1,94 @@
+use pinocchio::program_error::ProgramError;
+
+/// Errors specific to the AMM program.
+///
+/// Every variant is returned to the runtime as `ProgramError::Custom(code)`,
+/// where `code` is the explicit discriminant below. The codes are part of the
+/// program's public interface: never reorder, renumber or reuse them, only
+/// append new variants.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmmError {
+    /// An amount passed in the instruction data was zero.
+    ZeroAmount = 0,
+    /// The trade or liquidity operation did not meet the caller's slippage limit.
+    SlippageExceeded = 1,
+    /// A vault account does not match the vault recorded in the pool.
+    InvalidVault = 2,
+    /// A mint or token account mint does not match the pool.
+    InvalidMint = 3,
+    /// The LP mint does not match the LP mint recorded in the pool.
+    InvalidLpMint = 4,
+    /// A token account is not owned by the expected wallet.
+    InvalidTokenAccountOwner = 5,
+    /// The pool holds no liquidity.
+    EmptyPool = 6,
+    /// The pool reserves cannot cover the requested amount.
+    InsufficientLiquidity = 7,
+    /// The user holds fewer LP tokens than requested.
+    InsufficientLpBalance = 8,
+    /// The fee rate is above 10000 basis points.
+    InvalidFeeRate = 9,
+    /// Both sides of the pool use the same mint.
+    IdenticalMints = 10,
+    /// The pool account is not the PDA derived from the mints and bump.
+    InvalidPoolAddress = 11,
+    /// The LP mint account is not the PDA derived from the pool and bump.
+    InvalidLpMintAddress = 12,
+    /// A vault passed to initialize already holds tokens.
+    VaultNotEmpty = 13,
+    /// The operation would pay out zero tokens.
+    ZeroOutput = 14,
+    /// The instruction's deadline has passed.
+    DeadlineExceeded = 15,
+    /// The signer is not the pool authority.
+    InvalidAuthority = 16,
+    /// The pool already has an unpaid flash loan on this side.
+    FlashLoanActive = 17,
+    /// No matching `FlashRepay` follows the `FlashBorrow` in the transaction.
+    MissingFlashRepay = 18,
+    /// `FlashRepay` was called without an outstanding flash loan.
+    NoFlashLoan = 19,
+    /// A Token-2022 mint carries an extension the pool cannot support.
+    UnsupportedMintExtension = 20,
+    /// A vault received less than the operation requires, e.g. because the
+    /// mint charges a fee on transfer.
+    InsufficientAmountReceived = 21,
+    /// A vault account is not the PDA derived from the pool, mint and bump.
+    InvalidVaultAddress = 22,
+    /// The first deposit does not mint more than `MINIMUM_LIQUIDITY` LP tokens.
+    InsufficientInitialLiquidity = 23,
+    /// Swaps are disabled on this pool.
+    SwapsDisabled = 24,
+    /// Deposits are disabled on this pool.
+    DepositsDisabled = 25,
+    /// Withdrawals are disabled on this pool.
+    WithdrawalsDisabled = 26,
+    /// The pool still holds liquidity, fees or a flash loan and cannot be
+    /// closed.
+    PoolNotEmpty = 27,
+    /// The LP mint decimals are above `MAX_LP_DECIMALS`.
+    InvalidLpDecimals = 28,
+    /// The curve type is not one the program supports, or the operation is
+    /// not supported on the pool's curve.
+    InvalidCurveType = 29,
+    /// The amplification coefficient is outside `MIN_AMP..=MAX_AMP`, changes
+    /// by more than `MAX_AMP_CHANGE`, or is set on a constant-product pool.
+    InvalidAmp = 30,
+    /// The amplification ramp ends less than `MIN_RAMP_DURATION` from now.
+    InvalidRampDuration = 31,
+    /// The StableSwap invariant did not converge.
+    CurveNotConverged = 32,
+    /// A treasury token account is not owned by the protocol authority.
+    InvalidTreasury = 33,
+    /// The mints of a StableSwap pool have different decimals.
+    MismatchedDecimals = 34,
+    /// The protocol config account is not the PDA derived from `CONFIG_SEED`.
+    InvalidConfigAddress = 35,
+}
+
+impl From<AmmError> for ProgramError {
+    fn from(error: AmmError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
@@ -1,12 +1,141 @@
-pub fn integer_sqrt(n: u64) -> u64 {
-    if n == 0 {
-        return 0;
+use pinocchio::program_error::ProgramError;
+
+use crate::{
+    error::AmmError,
+    math::{integer_sqrt, mul_div_ceil, mul_div_floor},
+};
+
+/// Constant-product output for `amount_in`, returning `(amount_out, fee)`.
+///
+/// The fee is the part of `amount_in` that is not counted towards the price
+/// curve; it stays in the pool.
+pub fn calculate_swap_output(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_rate: u16,
+) -> Result<(u64, u64), ProgramError> {
+    // Rounding the fee-adjusted input and the output down keeps any remainder
+    // in the pool.
+    let fee_multiplier = 10000_u64
+        .checked_sub(fee_rate as u64)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let amount_in_with_fee = mul_div_floor(amount_in, fee_multiplier, 10000)?;
+
+    let amount_out = mul_div_floor(
+        reserve_out,
+        amount_in_with_fee,
+        reserve_in
+            .checked_add(amount_in_with_fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    )?;
+
+    Ok((amount_out, amount_in - amount_in_with_fee))
+}
+
+/// Smallest `amount_in` whose constant-product output covers `amount_out`,
+/// returning `(amount_in, fee)`.
+///
+/// Both divisions round up so the pool never pays out more than the curve
+/// allows.
+pub fn calculate_swap_input(
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_rate: u16,
+) -> Result<(u64, u64), ProgramError> {
+    if amount_out >= reserve_out {
+        return Err(AmmError::InsufficientLiquidity.into());
+    }
+
+    let amount_in_with_fee = mul_div_ceil(reserve_in, amount_out, reserve_out - amount_out)?;
+
+    let fee_multiplier = 10000_u64
+        .checked_sub(fee_rate as u64)
+        .filter(|multiplier| *multiplier > 0)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let amount_in = mul_div_ceil(amount_in_with_fee, 10000, fee_multiplier)?;
+
+    let (_, fee) = calculate_swap_output(amount_in, reserve_in, reserve_out, fee_rate)?;
+
+    Ok((amount_in, fee))
+}
+
+/// Fee owed on a flash loan of `amount`, rounded up so the pool never
+/// undercharges.
+pub fn calculate_flash_loan_fee(amount: u64, fee_rate: u16) -> Result<u64, ProgramError> {
+    mul_div_ceil(amount, fee_rate as u64, 10000)
+}
+
+/// Amounts of a deposit of at most `max_amount_a` and `max_amount_b` that
+/// match the ratio of the reserves, returning `(amount_a, amount_b)`.
+///
+/// One side is always deposited in full and the other is scaled down, rounding
+/// down so the pool never credits more than it receives. The first deposit,
+/// with no LP supply yet, takes both maximums, which set the initial price.
+pub fn calculate_deposit_amounts(
+    max_amount_a: u64,
+    max_amount_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> Result<(u64, u64), ProgramError> {
+    if lp_supply == 0 {
+        return Ok((max_amount_a, max_amount_b));
     }
-    let mut x = n;
-    let mut y = x.div_ceil(2) / 2;
-    while y < x {
-        x = y;
-        y = (x + n / x) / 2;
+    if reserve_a == 0 || reserve_b == 0 {
+        return Err(AmmError::EmptyPool.into());
+    }
+
+    // An optimal amount that does not fit in a `u64` is above `max_amount_b` too.
+    if let Some(amount_b_optimal) = mul_div_floor(max_amount_a, reserve_b, reserve_a)
+        .ok()
+        .filter(|amount_b| *amount_b <= max_amount_b)
+    {
+        return Ok((max_amount_a, amount_b_optimal));
+    }
+
+    // `max_amount_b` is below the optimal amount, so this is below `max_amount_a`.
+    let amount_a_optimal = mul_div_floor(max_amount_b, reserve_a, reserve_b)?;
+    Ok((amount_a_optimal, max_amount_b))
+}
+
+/// Part of a single-sided deposit of `amount_in` to swap into the other token
+/// so that the remainder and the swap output match the post-swap reserves.
+///
+/// Solves `s = (sqrt((R * K)^2 + 4 * F * 10000 * a * R) - R * K) / (2 * F)`
+/// with `F = 10000 - fee_rate` and `K = 10000 + F`, rounding down.
+pub fn calculate_single_deposit_swap(
+    amount_in: u64,
+    reserve_in: u64,
+    fee_rate: u16,
+) -> Result<u64, ProgramError> {
+    let fee_multiplier = 10000_u128
+        .checked_sub(fee_rate as u128)
+        .filter(|multiplier| *multiplier > 0)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let k = 10000 + fee_multiplier;
+    let reserve_in = reserve_in as u128;
+
+    // Below 2^94, as both terms are below 2^64 * 2^30.
+    let w = reserve_in * k * k + 4 * fee_multiplier * 10000 * amount_in as u128;
+    let root = sqrt_product(reserve_in, w);
+
+    let swap_amount = root.saturating_sub(reserve_in * k) / (2 * fee_multiplier);
+    Ok(swap_amount.min(amount_in as u128) as u64)
+}
+
+/// `floor(sqrt(x * y))`, approximated to about 60 significant bits when the
+/// product does not fit in a `u128`.
+fn sqrt_product(x: u128, y: u128) -> u128 {
+    if let Some(product) = x.checked_mul(y) {
+        return integer_sqrt(product);
     }
-    x
+
+    // Drop `shift` low bits from each factor, so the product loses an even
+    // number of bits and the root is exact up to a factor of `2^shift`.
+    let bits = 256 - x.leading_zeros() - y.leading_zeros();
+    let shift = (bits - 128).div_ceil(2);
+    integer_sqrt((x >> shift) * (y >> shift)) << shift
 }
@@ -1,26 +1,41 @@
 use bytemuck::{Pod, Zeroable};
 use pinocchio::{
-    ProgramResult, account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey,
+    ProgramResult,
+    account_info::AccountInfo,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{Sysvar, clock::Clock},
 };
 use pinocchio_token::{
-    instructions::{MintTo, Transfer},
+    instructions::MintTo,
     state::{Mint, TokenAccount},
 };
 
 use super::{
-    utils::{create_pool_seed, create_pool_signer, load_pool_data},
+    deadline::{Deadline, validate_deadline},
+    token::load_token_account,
+    utils::{
+        create_pool_seed, create_pool_signer, load_pool_data, receive_into, transfer_from_user,
+    },
     validators::{
-        validate_instruction_length, validate_non_zero, validate_pubkey_match, validate_signer,
-        validate_token_program,
+        validate_mint_token_program, validate_non_zero, validate_pool_status,
+        validate_pubkey_match, validate_signer, validate_token_program, validate_vault,
     },
 };
-use crate::{helper::integer_sqrt, states::Pool};
+use crate::{
+    constants::{DEPOSITS_DISABLED, MINIMUM_LIQUIDITY},
+    curve::SwapCurve,
+    error::AmmError,
+    helper::calculate_deposit_amounts,
+    states::Pool,
+};
 
 #[repr(C)]
 #[derive(Clone, Debug, Copy, PartialEq, Pod, Zeroable)]
 pub struct AddLiquidityInstructionData {
-    pub amount_a: u64,
-    pub amount_b: u64,
+    pub max_amount_a: u64,
+    pub max_amount_b: u64,
     pub min_lp_amount: u64,
 }
 
@@ -28,8 +43,29 @@ impl AddLiquidityInstructionData {
     pub const LEN: usize = core::mem::size_of::<Self>();
 }
 
+/// Return data written by `AddLiquidity`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct DepositAmounts {
+    /// Token A taken from the user, at most `max_amount_a`.
+    pub amount_a: u64,
+    /// Token B taken from the user, at most `max_amount_b`.
+    pub amount_b: u64,
+    /// LP tokens minted to the user.
+    pub lp_amount: u64,
+}
+
+impl DepositAmounts {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+/// Deposits both tokens in the ratio of the reserves, up to `max_amount_a`
+/// and `max_amount_b`, and mints LP tokens for them.
+///
+/// Only the ratio-matched amounts are transferred, so an unbalanced request
+/// leaves the excess with the user instead of donating it to the pool.
 pub fn process_add_liquidity(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction: &[u8],
 ) -> ProgramResult {
@@ -43,89 +79,159 @@ pub fn process_add_liquidity(
         user_token_b,
         user_lp_token,
         token_program,
-        _remaining @ ..,
+        mint_a,
+        mint_b,
+        token_program_a,
+        token_program_b,
+        lp_vault,
+        hook_accounts @ ..,
     ] = accounts
     else {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::NotEnoughAccountKeys);
     };
 
     validate_signer(user)?;
     validate_token_program(token_program)?;
-    validate_instruction_length(instruction, AddLiquidityInstructionData::LEN)?;
+
+    let (instruction, deadline) = Deadline::split(instruction, AddLiquidityInstructionData::LEN)?;
+    validate_deadline(deadline)?;
 
     let data = bytemuck::checked::pod_read_unaligned::<AddLiquidityInstructionData>(instruction);
 
-    validate_non_zero(data.amount_a)?;
-    validate_non_zero(data.amount_b)?;
+    validate_non_zero(data.max_amount_a)?;
+    validate_non_zero(data.max_amount_b)?;
 
-    let lp_tokens_to_mint = {
-        let mut pool_data = pool.try_borrow_mut_data()?;
-        let pool_state = Pool::load_mut(&mut pool_data)?;
+    let (deposit_a, deposit_b, reserve_a, reserve_b, total_lp_supply, lp_vault_bump, curve) = {
+        let pool_data = pool.try_borrow_data()?;
+        let pool_state = Pool::load(&pool_data)?;
 
         let lp_mint_acc = Mint::from_account_info(lp_mint)?;
-        let user_token_a_acc = TokenAccount::from_account_info(user_token_a)?;
-        let user_token_b_acc = TokenAccount::from_account_info(user_token_b)?;
+        let user_token_a_acc = load_token_account(user_token_a)?;
+        let user_token_b_acc = load_token_account(user_token_b)?;
         let user_lp_token_acc = TokenAccount::from_account_info(user_lp_token)?;
 
-        validate_pubkey_match(lp_mint.key(), &pool_state.lp_mint)?;
-        validate_pubkey_match(vault_a.key(), &pool_state.vault_a)?;
-        validate_pubkey_match(vault_b.key(), &pool_state.vault_b)?;
-        validate_pubkey_match(user_token_a_acc.mint(), &pool_state.token_a)?;
-        validate_pubkey_match(user_token_b_acc.mint(), &pool_state.token_b)?;
-        validate_pubkey_match(user_lp_token_acc.mint(), &pool_state.lp_mint)?;
-
-        let total_lp_supply = lp_mint_acc.supply();
-
-        let lp_tokens_to_mint = if pool_state.reserve_a == 0 && pool_state.reserve_b == 0 {
-            integer_sqrt(
-                data.amount_a
-                    .checked_mul(data.amount_b)
-                    .ok_or(ProgramError::ArithmeticOverflow)?,
-            )
-        } else {
-            let a = data
-                .amount_a
-                .checked_mul(total_lp_supply)
-                .ok_or(ProgramError::ArithmeticOverflow)?
-                .checked_div(pool_state.reserve_a)
-                .ok_or(ProgramError::ArithmeticOverflow)?;
-
-            let b = data
-                .amount_b
-                .checked_mul(total_lp_supply)
-                .ok_or(ProgramError::ArithmeticOverflow)?
-                .checked_div(pool_state.reserve_b)
-                .ok_or(ProgramError::ArithmeticOverflow)?;
-
-            a.min(b)
-        };
-
-        if lp_tokens_to_mint < data.min_lp_amount {
-            return Err(ProgramError::InsufficientFunds);
-        }
+        validate_pool_status(pool_state, DEPOSITS_DISABLED, AmmError::DepositsDisabled)?;
+        validate_pubkey_match(lp_mint.key(), &pool_state.lp_mint, AmmError::InvalidLpMint)?;
+        validate_vault(
+            program_id,
+            pool.key(),
+            vault_a.key(),
+            &pool_state.token_a,
+            pool_state.vault_a_bump,
+        )?;
+        validate_vault(
+            program_id,
+            pool.key(),
+            vault_b.key(),
+            &pool_state.token_b,
+            pool_state.vault_b_bump,
+        )?;
+        validate_pubkey_match(mint_a.key(), &pool_state.token_a, AmmError::InvalidMint)?;
+        validate_pubkey_match(mint_b.key(), &pool_state.token_b, AmmError::InvalidMint)?;
+        validate_mint_token_program(token_program_a, &pool_state.token_program_a)?;
+        validate_mint_token_program(token_program_b, &pool_state.token_program_b)?;
+        validate_pubkey_match(
+            user_token_a_acc.mint(),
+            &pool_state.token_a,
+            AmmError::InvalidMint,
+        )?;
+        validate_pubkey_match(
+            user_token_b_acc.mint(),
+            &pool_state.token_b,
+            AmmError::InvalidMint,
+        )?;
+        validate_pubkey_match(
+            user_lp_token_acc.mint(),
+            &pool_state.lp_mint,
+            AmmError::InvalidLpMint,
+        )?;
 
-        lp_tokens_to_mint
+        let (deposit_a, deposit_b) = calculate_deposit_amounts(
+            data.max_amount_a,
+            data.max_amount_b,
+            pool_state.reserve_a,
+            pool_state.reserve_b,
+            lp_mint_acc.supply(),
+        )?;
+        validate_non_zero(deposit_a)?;
+        validate_non_zero(deposit_b)?;
+
+        (
+            deposit_a,
+            deposit_b,
+            pool_state.reserve_a,
+            pool_state.reserve_b,
+            lp_mint_acc.supply(),
+            pool_state.lp_vault_bump,
+            pool_state.curve(Clock::get()?.unix_timestamp)?,
+        )
     };
 
-    Transfer {
-        from: user_token_a,
-        to: vault_a,
-        authority: user,
-        amount: data.amount_a,
-    }
-    .invoke()?;
+    // LP tokens and reserves are based on what the vaults received, which is
+    // less than requested for mints that charge a fee on transfer.
+    let amount_a = receive_into(vault_a, || {
+        transfer_from_user(
+            user,
+            user_token_a,
+            mint_a,
+            vault_a,
+            token_program_a,
+            deposit_a,
+            hook_accounts,
+        )
+    })?;
+
+    let amount_b = receive_into(vault_b, || {
+        transfer_from_user(
+            user,
+            user_token_b,
+            mint_b,
+            vault_b,
+            token_program_b,
+            deposit_b,
+            hook_accounts,
+        )
+    })?;
+
+    // The first deposit locks `MINIMUM_LIQUIDITY` of its LP tokens in the LP
+    // vault, so the supply can never be withdrawn back to zero.
+    let liquidity = curve.deposit(amount_a, amount_b, reserve_a, reserve_b, total_lp_supply)?;
+    let (lp_tokens_to_mint, locked_liquidity) = if total_lp_supply == 0 {
+        if liquidity <= MINIMUM_LIQUIDITY {
+            return Err(AmmError::InsufficientInitialLiquidity.into());
+        }
+
+        (liquidity - MINIMUM_LIQUIDITY, MINIMUM_LIQUIDITY)
+    } else {
+        (liquidity, 0)
+    };
 
-    Transfer {
-        from: user_token_b,
-        to: vault_b,
-        authority: user,
-        amount: data.amount_b,
+    if lp_tokens_to_mint < data.min_lp_amount {
+        return Err(AmmError::SlippageExceeded.into());
     }
-    .invoke()?;
 
-    let (pool_bump, token_a, token_b) = load_pool_data(pool)?;
+    let (pool_bump, token_a, token_b, curve_type) = load_pool_data(pool)?;
     let binding = [pool_bump];
-    let pool_seed = create_pool_seed(&binding, &token_a, &token_b);
+    let curve_binding = [curve_type];
+    let pool_seed = create_pool_seed(&binding, &token_a, &token_b, &curve_binding);
+
+    if locked_liquidity > 0 {
+        validate_vault(
+            program_id,
+            pool.key(),
+            lp_vault.key(),
+            lp_mint.key(),
+            lp_vault_bump,
+        )?;
+
+        MintTo {
+            mint: lp_mint,
+            mint_authority: pool,
+            account: lp_vault,
+            amount: locked_liquidity,
+        }
+        .invoke_signed(&[create_pool_signer(&pool_seed)])?;
+    }
 
     MintTo {
         mint: lp_mint,
@@ -138,15 +244,23 @@ pub fn process_add_liquidity(
     let mut pool_data = pool.try_borrow_mut_data()?;
     let pool_state = Pool::load_mut(&mut pool_data)?;
 
+    pool_state.update_oracle(Clock::get()?.unix_timestamp)?;
+
     pool_state.reserve_a = pool_state
         .reserve_a
-        .checked_add(data.amount_a)
+        .checked_add(amount_a)
         .ok_or(ProgramError::ArithmeticOverflow)?;
 
     pool_state.reserve_b = pool_state
         .reserve_b
-        .checked_add(data.amount_b)
+        .checked_add(amount_b)
         .ok_or(ProgramError::ArithmeticOverflow)?;
 
+    set_return_data(bytemuck::bytes_of(&DepositAmounts {
+        amount_a: deposit_a,
+        amount_b: deposit_b,
+        lp_amount: lp_tokens_to_mint,
+    }));
+
     Ok(())
 }
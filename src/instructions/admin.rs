@@ -0,0 +1,233 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{Sysvar, clock::Clock},
+};
+
+use super::validators::{
+    validate_authority, validate_instruction_length, validate_pubkey_match, validate_signer,
+};
+use crate::{
+    constants::{MAX_AMP, MAX_AMP_CHANGE, MIN_AMP, MIN_RAMP_DURATION, POOL_STATUS_MASK},
+    curve::CURVE_STABLE_SWAP,
+    error::AmmError,
+    states::Pool,
+};
+
+#[repr(C)]
+#[derive(Clone, Debug, Copy, PartialEq, Pod, Zeroable)]
+pub struct UpdatePoolInstructionData {
+    pub fee_rate: u16,
+    pub flash_loan_fee_rate: u16,
+}
+
+impl UpdatePoolInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Copy, PartialEq, Pod, Zeroable)]
+pub struct ProposeAuthorityInstructionData {
+    pub new_authority: Pubkey,
+}
+
+impl ProposeAuthorityInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Copy, PartialEq, Pod, Zeroable)]
+pub struct RampAmpInstructionData {
+    pub target_amp: u64,
+    pub ramp_stop_timestamp: i64,
+}
+
+impl RampAmpInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+/// Sets the swap and flash loan fee rates of the pool. The protocol fee rate
+/// belongs to the protocol authority, through `SetProtocolFeeRate`.
+pub fn process_update_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: &[u8],
+) -> ProgramResult {
+    let [authority, pool, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    validate_instruction_length(instruction, UpdatePoolInstructionData::LEN)?;
+
+    let data = bytemuck::checked::pod_read_unaligned::<UpdatePoolInstructionData>(instruction);
+
+    if data.fee_rate > 10000 || data.flash_loan_fee_rate > 10000 {
+        return Err(AmmError::InvalidFeeRate.into());
+    }
+
+    let mut pool_data = pool.try_borrow_mut_data()?;
+    let pool_state = Pool::load_mut(&mut pool_data)?;
+
+    validate_authority(authority, pool_state)?;
+
+    pool_state.fee_rate = data.fee_rate;
+    pool_state.flash_loan_fee_rate = data.flash_loan_fee_rate;
+
+    Ok(())
+}
+
+/// Proposes `new_authority` as the next pool authority. It only takes over
+/// once it signs `AcceptAuthority`; proposing the zero key cancels a pending
+/// transfer.
+pub fn process_propose_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: &[u8],
+) -> ProgramResult {
+    let [authority, pool, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    validate_instruction_length(instruction, ProposeAuthorityInstructionData::LEN)?;
+
+    let data =
+        bytemuck::checked::pod_read_unaligned::<ProposeAuthorityInstructionData>(instruction);
+
+    let mut pool_data = pool.try_borrow_mut_data()?;
+    let pool_state = Pool::load_mut(&mut pool_data)?;
+
+    validate_authority(authority, pool_state)?;
+
+    pool_state.pending_authority = data.new_authority;
+
+    Ok(())
+}
+
+/// Makes the signing pending authority the pool authority.
+pub fn process_accept_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction: &[u8],
+) -> ProgramResult {
+    let [pending_authority, pool, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    validate_signer(pending_authority)?;
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let mut pool_data = pool.try_borrow_mut_data()?;
+    let pool_state = Pool::load_mut(&mut pool_data)?;
+
+    // An empty proposal is the zero key, which can never sign.
+    validate_pubkey_match(
+        pending_authority.key(),
+        &pool_state.pending_authority,
+        AmmError::InvalidAuthority,
+    )?;
+
+    pool_state.authority = pool_state.pending_authority;
+    pool_state.pending_authority = [0; 32];
+
+    Ok(())
+}
+
+/// Sets the pool status bits, e.g. `SWAPS_DISABLED | DEPOSITS_DISABLED` for a
+/// withdraw-only pool. Zero reopens every operation.
+pub fn process_set_pool_status(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: &[u8],
+) -> ProgramResult {
+    let [authority, pool, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    validate_instruction_length(instruction, 1)?;
+
+    let status = instruction[0];
+
+    if status & !POOL_STATUS_MASK != 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut pool_data = pool.try_borrow_mut_data()?;
+    let pool_state = Pool::load_mut(&mut pool_data)?;
+
+    validate_authority(authority, pool_state)?;
+
+    pool_state.status = status;
+
+    Ok(())
+}
+
+/// Moves the amplification coefficient of a StableSwap pool linearly from its
+/// current value to `target_amp`, reaching it at `ramp_stop_timestamp`.
+///
+/// A ramp lasts at least `MIN_RAMP_DURATION` and changes the coefficient by at
+/// most a factor of `MAX_AMP_CHANGE`, so no single step moves prices sharply.
+/// Starting a ramp replaces any ramp in progress.
+pub fn process_ramp_amp(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: &[u8],
+) -> ProgramResult {
+    let [authority, pool, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    validate_instruction_length(instruction, RampAmpInstructionData::LEN)?;
+
+    let data = bytemuck::checked::pod_read_unaligned::<RampAmpInstructionData>(instruction);
+
+    let mut pool_data = pool.try_borrow_mut_data()?;
+    let pool_state = Pool::load_mut(&mut pool_data)?;
+
+    validate_authority(authority, pool_state)?;
+
+    if pool_state.curve_type != CURVE_STABLE_SWAP {
+        return Err(AmmError::InvalidCurveType.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if data.ramp_stop_timestamp < now.saturating_add(MIN_RAMP_DURATION) {
+        return Err(AmmError::InvalidRampDuration.into());
+    }
+
+    let current_amp = pool_state.amp(now);
+    if !(MIN_AMP..=MAX_AMP).contains(&data.target_amp)
+        || data.target_amp > current_amp.saturating_mul(MAX_AMP_CHANGE)
+        || data.target_amp.saturating_mul(MAX_AMP_CHANGE) < current_amp
+    {
+        return Err(AmmError::InvalidAmp.into());
+    }
+
+    pool_state.initial_amp = current_amp;
+    pool_state.target_amp = data.target_amp;
+    pool_state.ramp_start_timestamp = now;
+    pool_state.ramp_stop_timestamp = data.ramp_stop_timestamp;
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use pinocchio::{
+    ProgramResult, account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey,
+};
+use pinocchio_token::{instructions::Burn, state::Mint};
+use pinocchio_token_2022::instructions::CloseAccount;
+
+use super::{
+    token::load_token_account,
+    utils::{create_pool_seed, create_pool_signer, transfer_from_pool},
+    validators::{
+        validate_authority, validate_mint_token_program, validate_pubkey_match,
+        validate_token_program, validate_vault,
+    },
+};
+use crate::{error::AmmError, states::Pool};
+
+/// Closes a pool whose only LP tokens left are the ones locked in its LP
+/// vault, e.g. after every LP has withdrawn.
+///
+/// The locked LP tokens are burned, and whatever the vaults still hold, the
+/// reserves backing that locked share and any donations, goes to the
+/// destination token accounts. The vaults are then closed through their token
+/// programs and the rent of every closed account goes to `destination`.
+///
+/// The pool data is zeroed before the account is closed, so nothing in the
+/// same transaction can read it back. The LP mint is an SPL Token mint and
+/// cannot be closed; it stays behind with no supply, and `Initialize` reuses it
+/// when a pool is recreated at the same address.
+pub fn process_close_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction: &[u8],
+) -> ProgramResult {
+    let [
+        authority,
+        pool,
+        lp_mint,
+        vault_a,
+        vault_b,
+        lp_vault,
+        destination,
+        destination_token_a,
+        destination_token_b,
+        mint_a,
+        mint_b,
+        token_program,
+        token_program_a,
+        token_program_b,
+        hook_accounts @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    validate_token_program(token_program)?;
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    if destination.key() == pool.key() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let (pool_bump, token_a, token_b, curve_type, locked_liquidity, balance_a, balance_b) = {
+        let pool_data = pool.try_borrow_data()?;
+        let pool_state = Pool::load(&pool_data)?;
+
+        validate_authority(authority, pool_state)?;
+        validate_pubkey_match(lp_mint.key(), &pool_state.lp_mint, AmmError::InvalidLpMint)?;
+        validate_vault(
+            program_id,
+            pool.key(),
+            vault_a.key(),
+            &pool_state.token_a,
+            pool_state.vault_a_bump,
+        )?;
+        validate_vault(
+            program_id,
+            pool.key(),
+            vault_b.key(),
+            &pool_state.token_b,
+            pool_state.vault_b_bump,
+        )?;
+        validate_vault(
+            program_id,
+            pool.key(),
+            lp_vault.key(),
+            &pool_state.lp_mint,
+            pool_state.lp_vault_bump,
+        )?;
+        validate_pubkey_match(mint_a.key(), &pool_state.token_a, AmmError::InvalidMint)?;
+        validate_pubkey_match(mint_b.key(), &pool_state.token_b, AmmError::InvalidMint)?;
+        validate_mint_token_program(token_program_a, &pool_state.token_program_a)?;
+        validate_mint_token_program(token_program_b, &pool_state.token_program_b)?;
+        validate_pubkey_match(
+            load_token_account(destination_token_a)?.mint(),
+            &pool_state.token_a,
+            AmmError::InvalidMint,
+        )?;
+        validate_pubkey_match(
+            load_token_account(destination_token_b)?.mint(),
+            &pool_state.token_b,
+            AmmError::InvalidMint,
+        )?;
+
+        // Protocol fees belong to the treasury and must be collected first.
+        let locked_liquidity = load_token_account(lp_vault)?.amount();
+        if Mint::from_account_info(lp_mint)?.supply() != locked_liquidity
+            || pool_state.protocol_fees_a != 0
+            || pool_state.protocol_fees_b != 0
+            || pool_state.flash_loan_a != 0
+            || pool_state.flash_loan_b != 0
+        {
+            return Err(AmmError::PoolNotEmpty.into());
+        }
+
+        (
+            pool_state.bump,
+            Pubkey::from(pool_state.token_a),
+            Pubkey::from(pool_state.token_b),
+            pool_state.curve_type,
+            locked_liquidity,
+            load_token_account(vault_a)?.amount(),
+            load_token_account(vault_b)?.amount(),
+        )
+    };
+
+    let binding = [pool_bump];
+    let curve_binding = [curve_type];
+    let pool_seed = create_pool_seed(&binding, &token_a, &token_b, &curve_binding);
+
+    if locked_liquidity > 0 {
+        Burn {
+            mint: lp_mint,
+            account: lp_vault,
+            authority: pool,
+            amount: locked_liquidity,
+        }
+        .invoke_signed(&[create_pool_signer(&pool_seed)])?;
+    }
+
+    for (vault, mint, to, vault_token_program, balance) in [
+        (
+            vault_a,
+            mint_a,
+            destination_token_a,
+            token_program_a,
+            balance_a,
+        ),
+        (
+            vault_b,
+            mint_b,
+            destination_token_b,
+            token_program_b,
+            balance_b,
+        ),
+    ] {
+        if balance > 0 {
+            transfer_from_pool(
+                pool,
+                vault,
+                mint,
+                to,
+                vault_token_program,
+                balance,
+                hook_accounts,
+            )?;
+        }
+    }
+
+    for (vault, vault_token_program) in [
+        (vault_a, token_program_a),
+        (vault_b, token_program_b),
+        (lp_vault, token_program),
+    ] {
+        CloseAccount {
+            account: vault,
+            destination,
+            authority: pool,
+            token_program: vault_token_program.key(),
+        }
+        .invoke_signed(&[create_pool_signer(&pool_seed)])?;
+    }
+
+    pool.try_borrow_mut_data()?.fill(0);
+
+    *destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(pool.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    pool.close()
+}
@@ -0,0 +1,132 @@
+use pinocchio::{
+    ProgramResult, account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey,
+};
+
+use super::{
+    token::load_token_account,
+    utils::transfer_from_pool,
+    validators::{
+        validate_config, validate_mint_token_program, validate_pubkey_match, validate_signer,
+        validate_vault,
+    },
+};
+use crate::{error::AmmError, states::Pool};
+
+/// Pays the accrued protocol fees out to the protocol treasury.
+///
+/// Pools are created by anyone, so the fees go to the protocol authority in the
+/// protocol config rather than to the pool authority.
+pub fn process_collect_protocol_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction: &[u8],
+) -> ProgramResult {
+    let [
+        authority,
+        config,
+        pool,
+        vault_a,
+        vault_b,
+        treasury_token_a,
+        treasury_token_b,
+        mint_a,
+        mint_b,
+        token_program_a,
+        token_program_b,
+        hook_accounts @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    validate_signer(authority)?;
+
+    let protocol_authority = validate_config(program_id, config)?.authority;
+    validate_pubkey_match(
+        authority.key(),
+        &protocol_authority,
+        AmmError::InvalidAuthority,
+    )?;
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let (protocol_fees_a, protocol_fees_b) = {
+        let mut pool_data = pool.try_borrow_mut_data()?;
+        let pool_state = Pool::load_mut(&mut pool_data)?;
+
+        let treasury_token_a_acc = load_token_account(treasury_token_a)?;
+        let treasury_token_b_acc = load_token_account(treasury_token_b)?;
+
+        validate_vault(
+            program_id,
+            pool.key(),
+            vault_a.key(),
+            &pool_state.token_a,
+            pool_state.vault_a_bump,
+        )?;
+        validate_vault(
+            program_id,
+            pool.key(),
+            vault_b.key(),
+            &pool_state.token_b,
+            pool_state.vault_b_bump,
+        )?;
+        validate_pubkey_match(mint_a.key(), &pool_state.token_a, AmmError::InvalidMint)?;
+        validate_pubkey_match(mint_b.key(), &pool_state.token_b, AmmError::InvalidMint)?;
+        validate_mint_token_program(token_program_a, &pool_state.token_program_a)?;
+        validate_mint_token_program(token_program_b, &pool_state.token_program_b)?;
+        validate_pubkey_match(
+            treasury_token_a_acc.mint(),
+            &pool_state.token_a,
+            AmmError::InvalidMint,
+        )?;
+        validate_pubkey_match(
+            treasury_token_b_acc.mint(),
+            &pool_state.token_b,
+            AmmError::InvalidMint,
+        )?;
+        validate_pubkey_match(
+            treasury_token_a_acc.owner(),
+            &protocol_authority,
+            AmmError::InvalidTreasury,
+        )?;
+        validate_pubkey_match(
+            treasury_token_b_acc.owner(),
+            &protocol_authority,
+            AmmError::InvalidTreasury,
+        )?;
+
+        let collected = (pool_state.protocol_fees_a, pool_state.protocol_fees_b);
+        pool_state.protocol_fees_a = 0;
+        pool_state.protocol_fees_b = 0;
+        collected
+    };
+
+    if protocol_fees_a > 0 {
+        transfer_from_pool(
+            pool,
+            vault_a,
+            mint_a,
+            treasury_token_a,
+            token_program_a,
+            protocol_fees_a,
+            hook_accounts,
+        )?;
+    }
+
+    if protocol_fees_b > 0 {
+        transfer_from_pool(
+            pool,
+            vault_b,
+            mint_b,
+            treasury_token_b,
+            token_program_b,
+            protocol_fees_b,
+            hook_accounts,
+        )?;
+    }
+
+    Ok(())
+}
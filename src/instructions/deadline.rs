@@ -0,0 +1,62 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    ProgramResult,
+    program_error::ProgramError,
+    sysvars::{Sysvar, clock::Clock},
+};
+
+use crate::error::AmmError;
+
+/// `Deadline::value` is a unix timestamp in seconds.
+pub const DEADLINE_UNIX_TIMESTAMP: u8 = 0;
+
+/// `Deadline::value` is a slot.
+pub const DEADLINE_SLOT: u8 = 1;
+
+/// Optional expiry appended after the fixed instruction data of `Swap`,
+/// `SwapExactOut`, `AddLiquidity` and `Withdraw`.
+///
+/// The instruction is rejected once the clock is past `value`.
+#[repr(C, packed)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct Deadline {
+    pub kind: u8,
+    pub value: u64,
+}
+
+impl Deadline {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+
+    /// Splits `instruction` into its first `len` bytes and the deadline that
+    /// may follow them.
+    pub fn split(instruction: &[u8], len: usize) -> Result<(&[u8], Option<Self>), ProgramError> {
+        match instruction.len() {
+            n if n == len => Ok((instruction, None)),
+            n if n == len + Self::LEN => {
+                let (data, deadline) = instruction.split_at(len);
+                Ok((data, Some(bytemuck::checked::pod_read_unaligned(deadline))))
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+
+    pub fn check(&self, clock: &Clock) -> ProgramResult {
+        let now = match self.kind {
+            DEADLINE_UNIX_TIMESTAMP => u64::try_from(clock.unix_timestamp).unwrap_or(0),
+            DEADLINE_SLOT => clock.slot,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        if now > self.value {
+            return Err(AmmError::DeadlineExceeded.into());
+        }
+        Ok(())
+    }
+}
+
+pub fn validate_deadline(deadline: Option<Deadline>) -> ProgramResult {
+    match deadline {
+        Some(deadline) => deadline.check(&Clock::get()?),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,213 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{Sysvar, clock::Clock},
+};
+use pinocchio_token::{
+    instructions::MintTo,
+    state::{Mint, TokenAccount},
+};
+
+use super::{
+    deadline::{Deadline, validate_deadline},
+    token::load_token_account,
+    utils::{
+        create_pool_seed, create_pool_signer, load_pool_data, receive_into, transfer_from_user,
+    },
+    validators::{
+        validate_non_zero, validate_pool_side, validate_pool_status, validate_pubkey_match,
+        validate_signer, validate_token_program,
+    },
+};
+use crate::{
+    constants::{DEPOSITS_DISABLED, SWAPS_DISABLED},
+    curve::{CURVE_CONSTANT_PRODUCT, SwapCurve},
+    error::AmmError,
+    helper::calculate_single_deposit_swap,
+    states::Pool,
+};
+
+#[repr(C)]
+#[derive(Clone, Debug, Copy, PartialEq, Pod, Zeroable)]
+pub struct DepositSingleInstructionData {
+    pub amount_in: u64,
+    pub min_lp_amount: u64,
+}
+
+impl DepositSingleInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+/// Deposits one token and mints LP tokens for it.
+///
+/// Part of the deposit is swapped against the pool, paying the swap fee, so the
+/// rest and the swap output form a balanced deposit. Only the input token
+/// moves: the swap output never leaves the pool, it is added straight back to
+/// the reserve it came from.
+pub fn process_deposit_single(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: &[u8],
+) -> ProgramResult {
+    let [
+        user,
+        pool,
+        lp_mint,
+        input_vault,
+        user_input_token,
+        user_lp_token,
+        token_program,
+        input_mint,
+        input_token_program,
+        hook_accounts @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    validate_signer(user)?;
+    validate_token_program(token_program)?;
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let (instruction, deadline) = Deadline::split(instruction, DepositSingleInstructionData::LEN)?;
+    validate_deadline(deadline)?;
+
+    let data = bytemuck::checked::pod_read_unaligned::<DepositSingleInstructionData>(instruction);
+
+    validate_non_zero(data.amount_in)?;
+
+    let (is_a, total_lp_supply) = {
+        let pool_data = pool.try_borrow_data()?;
+        let pool_state = Pool::load(&pool_data)?;
+
+        let lp_mint_acc = Mint::from_account_info(lp_mint)?;
+        let user_input_token_acc = load_token_account(user_input_token)?;
+        let user_lp_token_acc = TokenAccount::from_account_info(user_lp_token)?;
+
+        // Part of the deposit is swapped, so it needs swaps enabled as well.
+        validate_pool_status(pool_state, DEPOSITS_DISABLED, AmmError::DepositsDisabled)?;
+        validate_pool_status(pool_state, SWAPS_DISABLED, AmmError::SwapsDisabled)?;
+        // The swap split is solved for the constant-product curve.
+        if pool_state.curve_type != CURVE_CONSTANT_PRODUCT {
+            return Err(AmmError::InvalidCurveType.into());
+        }
+        validate_pubkey_match(lp_mint.key(), &pool_state.lp_mint, AmmError::InvalidLpMint)?;
+        let is_a = validate_pool_side(
+            program_id,
+            pool.key(),
+            pool_state,
+            input_vault,
+            input_mint,
+            input_token_program,
+        )?;
+        validate_pubkey_match(
+            user_input_token_acc.mint(),
+            input_mint.key(),
+            AmmError::InvalidMint,
+        )?;
+        validate_pubkey_match(
+            user_lp_token_acc.mint(),
+            &pool_state.lp_mint,
+            AmmError::InvalidLpMint,
+        )?;
+
+        if pool_state.reserve_a == 0 || pool_state.reserve_b == 0 || lp_mint_acc.supply() == 0 {
+            return Err(AmmError::EmptyPool.into());
+        }
+
+        (is_a, lp_mint_acc.supply())
+    };
+
+    let amount_in = receive_into(input_vault, || {
+        transfer_from_user(
+            user,
+            user_input_token,
+            input_mint,
+            input_vault,
+            input_token_program,
+            data.amount_in,
+            hook_accounts,
+        )
+    })?;
+
+    let lp_tokens_to_mint = {
+        let mut pool_data = pool.try_borrow_mut_data()?;
+        let pool_state = Pool::load_mut(&mut pool_data)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        pool_state.update_oracle(now)?;
+
+        let curve = pool_state.curve(now)?;
+        let (reserve_in, reserve_out) = if is_a {
+            (pool_state.reserve_a, pool_state.reserve_b)
+        } else {
+            (pool_state.reserve_b, pool_state.reserve_a)
+        };
+
+        let swap_amount =
+            calculate_single_deposit_swap(amount_in, reserve_in, pool_state.fee_rate)?;
+        let (swap_out, fee) =
+            curve.swap_out(swap_amount, reserve_in, reserve_out, pool_state.fee_rate)?;
+        pool_state.apply_swap(is_a, swap_amount, swap_out, fee)?;
+
+        // Deposit the rest of the input and the swap output at the post-swap
+        // price. Rounding leaves any imbalance in the pool.
+        let deposit_in = amount_in - swap_amount;
+        let (amount_a, amount_b) = if is_a {
+            (deposit_in, swap_out)
+        } else {
+            (swap_out, deposit_in)
+        };
+        let lp_tokens_to_mint = curve.deposit(
+            amount_a,
+            amount_b,
+            pool_state.reserve_a,
+            pool_state.reserve_b,
+            total_lp_supply,
+        )?;
+
+        let (reserve_in, reserve_out) = if is_a {
+            (&mut pool_state.reserve_a, &mut pool_state.reserve_b)
+        } else {
+            (&mut pool_state.reserve_b, &mut pool_state.reserve_a)
+        };
+
+        *reserve_in = reserve_in
+            .checked_add(deposit_in)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        *reserve_out = reserve_out
+            .checked_add(swap_out)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        lp_tokens_to_mint
+    };
+
+    if lp_tokens_to_mint == 0 {
+        return Err(AmmError::ZeroOutput.into());
+    }
+
+    if lp_tokens_to_mint < data.min_lp_amount {
+        return Err(AmmError::SlippageExceeded.into());
+    }
+
+    let (pool_bump, token_a, token_b, curve_type) = load_pool_data(pool)?;
+    let binding = [pool_bump];
+    let curve_binding = [curve_type];
+    let pool_seed = create_pool_seed(&binding, &token_a, &token_b, &curve_binding);
+
+    MintTo {
+        mint: lp_mint,
+        mint_authority: pool,
+        account: user_lp_token,
+        amount: lp_tokens_to_mint,
+    }
+    .invoke_signed(&[create_pool_signer(&pool_seed)])?;
+
+    Ok(())
+}
@@ -0,0 +1,286 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{
+        Sysvar,
+        clock::Clock,
+        instructions::{Instructions, IntrospectedInstruction},
+    },
+};
+
+use super::{
+    utils::{receive_into, transfer_from_pool, transfer_from_user},
+    validators::{
+        validate_instruction_length, validate_mint_token_program, validate_non_zero,
+        validate_pool_status, validate_signer, validate_vault,
+    },
+};
+use crate::{
+    constants::SWAPS_DISABLED, error::AmmError, helper::calculate_flash_loan_fee, states::Pool,
+};
+
+/// Instruction discriminator of `FlashRepay`, looked up by `FlashBorrow`.
+const FLASH_REPAY_DISCRIMINATOR: u8 = 10;
+
+/// Positions of the pool and vault in the `FlashRepay` account list.
+const REPAY_POOL_INDEX: usize = 1;
+const REPAY_VAULT_INDEX: usize = 2;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct FlashBorrowInstructionData {
+    pub amount: u64,
+}
+
+impl FlashBorrowInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+/// Lends `amount` from one of the pool vaults for the rest of the transaction.
+///
+/// A `FlashRepay` for the same pool and vault must appear later in the
+/// transaction as a top-level instruction. The reserves are not touched, so a
+/// transaction that fails to repay leaves the pool exactly as it was.
+///
+/// Borrowing takes liquidity out of the vaults, so it is disabled along with
+/// swaps. Repaying is always allowed.
+pub fn process_flash_borrow(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instructions: &[u8],
+) -> ProgramResult {
+    let [
+        user,
+        pool,
+        vault,
+        mint,
+        user_token_account,
+        instructions_sysvar,
+        token_program,
+        hook_accounts @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    validate_signer(user)?;
+    validate_instruction_length(instructions, FlashBorrowInstructionData::LEN)?;
+
+    let data: FlashBorrowInstructionData = bytemuck::pod_read_unaligned(instructions);
+
+    validate_non_zero(data.amount)?;
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    {
+        let mut pool_data = pool.try_borrow_mut_data()?;
+        let pool_state = Pool::load_mut(&mut pool_data)?;
+
+        validate_pool_status(pool_state, SWAPS_DISABLED, AmmError::SwapsDisabled)?;
+
+        let (reserve, flash_loan) = flash_loan_side(
+            program_id,
+            pool.key(),
+            pool_state,
+            vault,
+            mint,
+            token_program,
+        )?;
+
+        if *flash_loan != 0 {
+            return Err(AmmError::FlashLoanActive.into());
+        }
+
+        if data.amount > *reserve {
+            return Err(AmmError::InsufficientLiquidity.into());
+        }
+
+        *flash_loan = data.amount;
+    }
+
+    validate_flash_repay(program_id, instructions_sysvar, pool.key(), vault.key())?;
+
+    transfer_from_pool(
+        pool,
+        vault,
+        mint,
+        user_token_account,
+        token_program,
+        data.amount,
+        hook_accounts,
+    )
+}
+
+/// Pays back the outstanding flash loan on `vault` plus the flash loan fee.
+///
+/// Everything the vault receives above the principal is added to the
+/// reserve, so the fee accrues to the LPs.
+pub fn process_flash_repay(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction: &[u8],
+) -> ProgramResult {
+    let [
+        user,
+        pool,
+        vault,
+        mint,
+        user_token_account,
+        token_program,
+        hook_accounts @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    validate_signer(user)?;
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let (principal, amount) = {
+        let mut pool_data = pool.try_borrow_mut_data()?;
+        let pool_state = Pool::load_mut(&mut pool_data)?;
+
+        let fee_rate = pool_state.flash_loan_fee_rate;
+        let (_, flash_loan) = flash_loan_side(
+            program_id,
+            pool.key(),
+            pool_state,
+            vault,
+            mint,
+            token_program,
+        )?;
+
+        let principal = *flash_loan;
+        if principal == 0 {
+            return Err(AmmError::NoFlashLoan.into());
+        }
+
+        let fee = calculate_flash_loan_fee(principal, fee_rate)?;
+        let amount = principal
+            .checked_add(fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        (principal, amount)
+    };
+
+    let amount_received = receive_into(vault, || {
+        transfer_from_user(
+            user,
+            user_token_account,
+            mint,
+            vault,
+            token_program,
+            amount,
+            hook_accounts,
+        )
+    })?;
+
+    if amount_received < amount {
+        return Err(AmmError::InsufficientAmountReceived.into());
+    }
+
+    let mut pool_data = pool.try_borrow_mut_data()?;
+    let pool_state = Pool::load_mut(&mut pool_data)?;
+
+    pool_state.update_oracle(Clock::get()?.unix_timestamp)?;
+
+    let (reserve, flash_loan) = flash_loan_side(
+        program_id,
+        pool.key(),
+        pool_state,
+        vault,
+        mint,
+        token_program,
+    )?;
+    *flash_loan = 0;
+    *reserve = reserve
+        .checked_add(amount_received - principal)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Returns the reserve and the outstanding flash loan counter for the side of
+/// `mint`, after checking `vault` and `token_program` against the same side.
+fn flash_loan_side<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    pool_state: &'a mut Pool,
+    vault: &AccountInfo,
+    mint: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<(&'a mut u64, &'a mut u64), ProgramError> {
+    if mint.key() == &pool_state.token_a {
+        validate_vault(
+            program_id,
+            pool,
+            vault.key(),
+            mint.key(),
+            pool_state.vault_a_bump,
+        )?;
+        validate_mint_token_program(token_program, &pool_state.token_program_a)?;
+        Ok((&mut pool_state.reserve_a, &mut pool_state.flash_loan_a))
+    } else if mint.key() == &pool_state.token_b {
+        validate_vault(
+            program_id,
+            pool,
+            vault.key(),
+            mint.key(),
+            pool_state.vault_b_bump,
+        )?;
+        validate_mint_token_program(token_program, &pool_state.token_program_b)?;
+        Ok((&mut pool_state.reserve_b, &mut pool_state.flash_loan_b))
+    } else {
+        Err(AmmError::InvalidMint.into())
+    }
+}
+
+/// Checks that a `FlashRepay` for `pool` and `vault` follows the current
+/// instruction in the transaction.
+fn validate_flash_repay(
+    program_id: &Pubkey,
+    instructions_sysvar: &AccountInfo,
+    pool: &Pubkey,
+    vault: &Pubkey,
+) -> ProgramResult {
+    let instructions = Instructions::try_from(instructions_sysvar)?;
+    let current = instructions.load_current_index() as usize;
+
+    for index in current + 1..instructions.num_instructions() as usize {
+        let instruction = instructions.load_instruction_at(index)?;
+        if is_flash_repay(&instruction, program_id, pool, vault) {
+            return Ok(());
+        }
+    }
+
+    Err(AmmError::MissingFlashRepay.into())
+}
+
+fn is_flash_repay(
+    instruction: &IntrospectedInstruction,
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    vault: &Pubkey,
+) -> bool {
+    if instruction.get_program_id() != program_id
+        || instruction.get_instruction_data().first() != Some(&FLASH_REPAY_DISCRIMINATOR)
+    {
+        return false;
+    }
+
+    matches!(
+        (
+            instruction.get_account_meta_at(REPAY_POOL_INDEX),
+            instruction.get_account_meta_at(REPAY_VAULT_INDEX),
+        ),
+        (Ok(pool_meta), Ok(vault_meta)) if &pool_meta.key == pool && &vault_meta.key == vault
+    )
+}
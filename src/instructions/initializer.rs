@@ -5,26 +5,39 @@ use pinocchio::{
     instruction::{Seed, Signer},
     program_error::ProgramError,
     pubkey::Pubkey,
-    sysvars::{Sysvar, rent::Rent},
+    sysvars::{Sysvar, clock::Clock, rent::Rent},
 };
 use pinocchio_system::instructions::CreateAccount;
 
+use super::{
+    token::{load_mint, token_program_of, validate_mint_extensions},
+    utils::create_vault,
+    validators::{validate_config, validate_mint_token_program},
+};
 use crate::{
-    constants::{LP_MINT_SEED, POOL_SEED, SYSTEM_PROGRAM_ID},
+    constants::{LP_MINT_SEED, MAX_LP_DECIMALS, POOL_SEED, SYSTEM_PROGRAM_ID, VAULT_SEED},
+    curve::{CURVE_STABLE_SWAP, Curve},
+    error::AmmError,
     states::Pool,
 };
-use pinocchio_token::{
-    ID,
-    instructions::InitializeMint2,
-    state::{Mint, TokenAccount},
-};
+use pinocchio_token::{ID, instructions::InitializeMint2, state::Mint};
 
-#[repr(C)]
+#[repr(C, packed)]
 #[derive(Clone, Debug, Copy, PartialEq, Pod, Zeroable)]
 pub struct InitializeInstructionData {
     pub fee_rate: u16,
+    /// Fee on flash loans, in basis points of the principal.
+    pub flash_loan_fee_rate: u16,
     pub pool_bump: u8,
     pub lp_mint_bump: u8,
+    pub vault_a_bump: u8,
+    pub vault_b_bump: u8,
+    pub lp_vault_bump: u8,
+    pub lp_decimals: u8,
+    pub curve_type: u8,
+    /// Amplification coefficient of a StableSwap pool; zero for a
+    /// constant-product pool.
+    pub amp: u64,
 }
 
 impl InitializeInstructionData {
@@ -46,6 +59,10 @@ pub fn process_initialize(
         vault_b,
         system_program,
         token_program,
+        token_program_a,
+        token_program_b,
+        lp_vault,
+        config,
         _remaining @ ..,
     ] = accounts
     else {
@@ -61,10 +78,10 @@ pub fn process_initialize(
     }
 
     if token_a.key() == token_b.key() {
-        return Err(ProgramError::InvalidArgument);
+        return Err(AmmError::IdenticalMints.into());
     }
 
-    if !lp_mint.data_is_empty() {
+    if !vault_a.data_is_empty() || !vault_b.data_is_empty() || !lp_vault.data_is_empty() {
         return Err(ProgramError::AccountAlreadyInitialized);
     }
 
@@ -76,23 +93,13 @@ pub fn process_initialize(
         return Err(ProgramError::IncorrectProgramId);
     }
 
-    let vault_a_account = TokenAccount::from_account_info(vault_a)?;
+    load_mint(token_a)?;
+    load_mint(token_b)?;
+    validate_mint_extensions(token_a)?;
+    validate_mint_extensions(token_b)?;
 
-    let vault_b_account = TokenAccount::from_account_info(vault_b)?;
-
-    if vault_a_account.mint() != token_a.key() {
-        return Err(ProgramError::InvalidAccountData);
-    }
-    if vault_a_account.amount() != 0 {
-        return Err(ProgramError::AccountAlreadyInitialized);
-    }
-
-    if vault_b_account.mint() != token_b.key() {
-        return Err(ProgramError::InvalidAccountData);
-    }
-    if vault_b_account.amount() != 0 {
-        return Err(ProgramError::AccountAlreadyInitialized);
-    }
+    validate_mint_token_program(token_program_a, &token_program_of(token_a)?)?;
+    validate_mint_token_program(token_program_b, &token_program_of(token_b)?)?;
 
     if instruction.len() != InitializeInstructionData::LEN {
         return Err(ProgramError::InvalidInstructionData);
@@ -102,22 +109,43 @@ pub fn process_initialize(
 
     //  - 1 basis point = 0.01%
     //  - 10000 basis points = 100%
-    if data.fee_rate > 10000 {
-        return Err(ProgramError::InvalidArgument);
+    if data.fee_rate > 10000 || data.flash_loan_fee_rate > 10000 {
+        return Err(AmmError::InvalidFeeRate.into());
+    }
+
+    if data.lp_decimals > MAX_LP_DECIMALS {
+        return Err(AmmError::InvalidLpDecimals.into());
+    }
+
+    Curve::new(data.curve_type, data.amp)?;
+
+    // StableSwap prices base units 1:1, which only holds for pegged mints of
+    // the same precision.
+    if data.curve_type == CURVE_STABLE_SWAP
+        && load_mint(token_a)?.decimals() != load_mint(token_b)?.decimals()
+    {
+        return Err(AmmError::MismatchedDecimals.into());
     }
 
+    // New pools take the protocol's fee share from the protocol config, not
+    // from their creator.
+    let protocol_fee_rate = validate_config(program_id, config)?.protocol_fee_rate;
+
+    let now = Clock::get()?.unix_timestamp;
+
     let pool_pda = pinocchio::pubkey::create_program_address(
         &[
             POOL_SEED.as_bytes(),
             token_a.key().as_ref(),
             token_b.key().as_ref(),
+            &[data.curve_type],
             &[data.pool_bump],
         ],
         program_id,
     )?;
 
     if pool.key() != &pool_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(AmmError::InvalidPoolAddress.into());
     }
 
     let lp_mint_pda = pinocchio::pubkey::create_program_address(
@@ -130,16 +158,45 @@ pub fn process_initialize(
     )?;
 
     if lp_mint.key() != &lp_mint_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(AmmError::InvalidLpMintAddress.into());
+    }
+
+    // `ClosePool` cannot close the LP mint, so a pool recreated at the same
+    // address takes over the old one once every LP token has been burned.
+    let reuse_lp_mint = !lp_mint.data_is_empty();
+    if reuse_lp_mint {
+        validate_closed_lp_mint(lp_mint, pool.key(), data.lp_decimals)?;
+    }
+
+    for (vault, mint, bump) in [
+        (vault_a, token_a, data.vault_a_bump),
+        (vault_b, token_b, data.vault_b_bump),
+        (lp_vault, lp_mint, data.lp_vault_bump),
+    ] {
+        let vault_pda = pinocchio::pubkey::create_program_address(
+            &[
+                VAULT_SEED.as_bytes(),
+                pool.key().as_ref(),
+                mint.key().as_ref(),
+                &[bump],
+            ],
+            program_id,
+        )?;
+
+        if vault.key() != &vault_pda {
+            return Err(AmmError::InvalidVaultAddress.into());
+        }
     }
 
     let rent = Rent::get()?;
 
     let binding = [data.pool_bump];
+    let curve_binding = [data.curve_type];
     let pool_seed = [
         Seed::from(POOL_SEED.as_bytes()),
         Seed::from(token_a.key().as_ref()),
         Seed::from(token_b.key().as_ref()),
+        Seed::from(&curve_binding),
         Seed::from(&binding),
     ];
 
@@ -154,11 +211,32 @@ pub fn process_initialize(
     })
     .invoke_signed(&[pool_seed_signer])?;
 
+    // The vaults are owned by the pool PDA and, being fresh accounts, carry no
+    // delegate or close authority.
+    create_vault(
+        authority,
+        pool,
+        vault_a,
+        token_a,
+        token_program_a,
+        data.vault_a_bump,
+        &rent,
+    )?;
+    create_vault(
+        authority,
+        pool,
+        vault_b,
+        token_b,
+        token_program_b,
+        data.vault_b_bump,
+        &rent,
+    )?;
+
     let mut pool_data = pool.try_borrow_mut_data()?;
     let pool_state = Pool::load_mut(&mut pool_data)?;
 
     pool_state.set_inner_full(Pool {
-        authority: *pool.key(),
+        authority: *authority.key(),
         token_a: *token_a.key(),
         token_b: *token_b.key(),
         lp_mint: *lp_mint.key(),
@@ -167,9 +245,30 @@ pub fn process_initialize(
         reserve_a: 0,
         reserve_b: 0,
         fee_rate: data.fee_rate,
+        protocol_fee_rate,
         bump: data.pool_bump,
         lp_mint_bump: data.lp_mint_bump,
-        _padding: [0; 4],
+        flash_loan_fee_rate: data.flash_loan_fee_rate,
+        protocol_fees_a: 0,
+        protocol_fees_b: 0,
+        last_update_timestamp: now,
+        price_a_cumulative: [0; 16],
+        price_b_cumulative: [0; 16],
+        flash_loan_a: 0,
+        flash_loan_b: 0,
+        token_program_a: *token_program_a.key(),
+        token_program_b: *token_program_b.key(),
+        pending_authority: [0; 32],
+        initial_amp: data.amp,
+        target_amp: data.amp,
+        ramp_start_timestamp: now,
+        ramp_stop_timestamp: now,
+        vault_a_bump: data.vault_a_bump,
+        vault_b_bump: data.vault_b_bump,
+        lp_vault_bump: data.lp_vault_bump,
+        status: 0,
+        curve_type: data.curve_type,
+        _padding: [0; 3],
     });
 
     let binding = [data.lp_mint_bump];
@@ -179,22 +278,52 @@ pub fn process_initialize(
         Seed::from(&binding),
     ];
 
-    (CreateAccount {
-        from: authority,
-        to: lp_mint,
-        space: Mint::LEN as u64,
-        lamports: rent.minimum_balance(Mint::LEN),
-        owner: &ID,
-    })
-    .invoke_signed(&[Signer::from(&lp_mint_seed[..])])?;
+    if !reuse_lp_mint {
+        (CreateAccount {
+            from: authority,
+            to: lp_mint,
+            space: Mint::LEN as u64,
+            lamports: rent.minimum_balance(Mint::LEN),
+            owner: &ID,
+        })
+        .invoke_signed(&[Signer::from(&lp_mint_seed[..])])?;
+
+        InitializeMint2 {
+            mint: lp_mint,
+            decimals: data.lp_decimals,
+            mint_authority: pool.key(),
+            freeze_authority: None,
+        }
+        .invoke_signed(&[Signer::from(&lp_mint_seed[..])])?;
+    }
+
+    // Holds the minimum liquidity locked by the first deposit.
+    create_vault(
+        authority,
+        pool,
+        lp_vault,
+        lp_mint,
+        token_program,
+        data.lp_vault_bump,
+        &rent,
+    )?;
+
+    Ok(())
+}
+
+/// Checks that `lp_mint` is the LP mint a closed pool at `pool` left behind:
+/// an SPL Token mint with no supply, minted only by the pool and with the
+/// requested decimals.
+fn validate_closed_lp_mint(lp_mint: &AccountInfo, pool: &Pubkey, lp_decimals: u8) -> ProgramResult {
+    let mint = Mint::from_account_info(lp_mint)?;
+
+    if mint.supply() != 0 || mint.mint_authority() != Some(pool) || mint.has_freeze_authority() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
 
-    InitializeMint2 {
-        mint: lp_mint,
-        decimals: 6,
-        mint_authority: pool.key(),
-        freeze_authority: None,
+    if mint.decimals() != lp_decimals {
+        return Err(AmmError::InvalidLpDecimals.into());
     }
-    .invoke_signed(&[Signer::from(&lp_mint_seed[..])])?;
 
     Ok(())
 }
@@ -6,6 +6,34 @@ pub mod withdraw;
 
 pub mod add_liquidity;
 
+pub mod deadline;
+
+pub mod quote_swap;
+
+pub mod swap_exact_out;
+
+pub mod route_swap;
+
+pub mod collect_protocol_fees;
+
+pub mod observe;
+
+pub mod flash_loan;
+
+pub mod sync_skim;
+
+pub mod deposit_single;
+
+pub mod withdraw_single;
+
+pub mod admin;
+
+pub mod close_pool;
+
+pub mod protocol_config;
+
+mod token;
+
 mod utils;
 
 mod validators;
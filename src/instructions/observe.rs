@@ -0,0 +1,32 @@
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{Sysvar, clock::Clock},
+};
+
+use crate::states::Pool;
+
+/// Returns the pool's `Observation` as of the current clock through return data.
+pub fn process_observe(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction: &[u8],
+) -> ProgramResult {
+    let [pool, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let pool_data = pool.try_borrow_data()?;
+    let observation = Pool::load(&pool_data)?.observe(Clock::get()?.unix_timestamp)?;
+
+    set_return_data(bytemuck::bytes_of(&observation));
+
+    Ok(())
+}
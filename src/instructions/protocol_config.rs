@@ -0,0 +1,236 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::{Pubkey, create_program_address, find_program_address},
+    sysvars::{Sysvar, rent::Rent},
+};
+use pinocchio_system::instructions::CreateAccount;
+
+use super::validators::{
+    validate_config, validate_instruction_length, validate_pubkey_match, validate_signer,
+};
+use crate::{
+    constants::{BPF_LOADER_UPGRADEABLE_ID, CONFIG_SEED, SYSTEM_PROGRAM_ID},
+    error::AmmError,
+    states::{Pool, ProtocolConfig},
+};
+
+/// `UpgradeableLoaderState::ProgramData` tag of a loader account.
+const PROGRAM_DATA_TAG: u32 = 3;
+
+/// Offset of the upgrade authority `Option<Pubkey>` in a `ProgramData`
+/// account, after the `u32` tag and the `u64` deployment slot.
+const PROGRAM_DATA_AUTHORITY_OFFSET: usize = 12;
+
+#[repr(C, packed)]
+#[derive(Clone, Debug, Copy, PartialEq, Pod, Zeroable)]
+pub struct InitializeConfigInstructionData {
+    pub authority: Pubkey,
+    pub protocol_fee_rate: u16,
+    pub config_bump: u8,
+}
+
+impl InitializeConfigInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+#[repr(C, packed)]
+#[derive(Clone, Debug, Copy, PartialEq, Pod, Zeroable)]
+pub struct UpdateConfigInstructionData {
+    pub authority: Pubkey,
+    pub protocol_fee_rate: u16,
+}
+
+impl UpdateConfigInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Copy, PartialEq, Pod, Zeroable)]
+pub struct SetProtocolFeeRateInstructionData {
+    pub protocol_fee_rate: u16,
+}
+
+impl SetProtocolFeeRateInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+/// Creates the protocol config with `authority` as the protocol authority and
+/// `protocol_fee_rate` as the protocol fee rate of new pools.
+///
+/// Only the program's upgrade authority can call it, and only once, so the
+/// protocol authority is never a key baked into the build.
+pub fn process_initialize_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: &[u8],
+) -> ProgramResult {
+    let [
+        upgrade_authority,
+        config,
+        program_data,
+        system_program,
+        _remaining @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    validate_signer(upgrade_authority)?;
+
+    if !config.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    if system_program.key() != &SYSTEM_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    validate_instruction_length(instruction, InitializeConfigInstructionData::LEN)?;
+
+    let data =
+        bytemuck::checked::pod_read_unaligned::<InitializeConfigInstructionData>(instruction);
+
+    if data.protocol_fee_rate > 10000 {
+        return Err(AmmError::InvalidFeeRate.into());
+    }
+
+    validate_upgrade_authority(program_id, program_data, upgrade_authority.key())?;
+
+    let config_pda =
+        create_program_address(&[CONFIG_SEED.as_bytes(), &[data.config_bump]], program_id)?;
+    validate_pubkey_match(config.key(), &config_pda, AmmError::InvalidConfigAddress)?;
+
+    let binding = [data.config_bump];
+    let config_seed = [Seed::from(CONFIG_SEED.as_bytes()), Seed::from(&binding)];
+
+    (CreateAccount {
+        from: upgrade_authority,
+        to: config,
+        space: ProtocolConfig::LEN as u64,
+        lamports: Rent::get()?.minimum_balance(ProtocolConfig::LEN),
+        owner: program_id,
+    })
+    .invoke_signed(&[Signer::from(&config_seed[..])])?;
+
+    let mut config_data = config.try_borrow_mut_data()?;
+    let config_state = ProtocolConfig::load_mut(&mut config_data)?;
+
+    config_state.authority = data.authority;
+    config_state.protocol_fee_rate = data.protocol_fee_rate;
+    config_state.bump = data.config_bump;
+
+    Ok(())
+}
+
+/// Hands the protocol authority over to `authority` and sets the protocol fee
+/// rate of new pools. Only the current protocol authority can call it.
+pub fn process_update_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: &[u8],
+) -> ProgramResult {
+    let [authority, config, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    validate_signer(authority)?;
+
+    let config_state = validate_config(program_id, config)?;
+    validate_pubkey_match(
+        authority.key(),
+        &config_state.authority,
+        AmmError::InvalidAuthority,
+    )?;
+
+    validate_instruction_length(instruction, UpdateConfigInstructionData::LEN)?;
+
+    let data = bytemuck::checked::pod_read_unaligned::<UpdateConfigInstructionData>(instruction);
+
+    if data.protocol_fee_rate > 10000 {
+        return Err(AmmError::InvalidFeeRate.into());
+    }
+
+    let mut config_data = config.try_borrow_mut_data()?;
+    let config_state = ProtocolConfig::load_mut(&mut config_data)?;
+
+    config_state.authority = data.authority;
+    config_state.protocol_fee_rate = data.protocol_fee_rate;
+
+    Ok(())
+}
+
+/// Sets the protocol fee rate of one pool. Only the protocol authority can
+/// call it; the pool authority cannot.
+pub fn process_set_protocol_fee_rate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: &[u8],
+) -> ProgramResult {
+    let [authority, config, pool, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    validate_signer(authority)?;
+
+    let config_state = validate_config(program_id, config)?;
+    validate_pubkey_match(
+        authority.key(),
+        &config_state.authority,
+        AmmError::InvalidAuthority,
+    )?;
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    validate_instruction_length(instruction, SetProtocolFeeRateInstructionData::LEN)?;
+
+    let data =
+        bytemuck::checked::pod_read_unaligned::<SetProtocolFeeRateInstructionData>(instruction);
+
+    if data.protocol_fee_rate > 10000 {
+        return Err(AmmError::InvalidFeeRate.into());
+    }
+
+    let mut pool_data = pool.try_borrow_mut_data()?;
+    Pool::load_mut(&mut pool_data)?.protocol_fee_rate = data.protocol_fee_rate;
+
+    Ok(())
+}
+
+/// Checks that `program_data` is this program's `ProgramData` account and
+/// records `authority` as its upgrade authority.
+fn validate_upgrade_authority(
+    program_id: &Pubkey,
+    program_data: &AccountInfo,
+    authority: &Pubkey,
+) -> ProgramResult {
+    if !program_data.is_owned_by(&BPF_LOADER_UPGRADEABLE_ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let (expected, _) = find_program_address(&[program_id.as_ref()], &BPF_LOADER_UPGRADEABLE_ID);
+    if program_data.key() != &expected {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let data = program_data.try_borrow_data()?;
+    let header = data
+        .get(..PROGRAM_DATA_AUTHORITY_OFFSET + 1 + 32)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    if header[..4] != PROGRAM_DATA_TAG.to_le_bytes() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // `Some(authority)`; an immutable program has no upgrade authority.
+    let upgrade_authority = &header[PROGRAM_DATA_AUTHORITY_OFFSET..];
+    if upgrade_authority[0] != 1 || upgrade_authority[1..] != authority[..] {
+        return Err(AmmError::InvalidAuthority.into());
+    }
+
+    Ok(())
+}
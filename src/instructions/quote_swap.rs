@@ -0,0 +1,96 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{Sysvar, clock::Clock},
+};
+
+use super::validators::{validate_instruction_length, validate_non_zero};
+use crate::{curve::SwapCurve, error::AmmError, states::Pool};
+
+#[repr(C, packed)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct QuoteSwapInstructionData {
+    pub amount_in: u64,
+    /// `1` quotes a token A to token B swap, `0` the reverse.
+    pub a_to_b: u8,
+}
+
+impl QuoteSwapInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+/// Return data written by `QuoteSwap`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct SwapQuote {
+    pub amount_out: u64,
+    pub fee: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+impl SwapQuote {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+pub fn process_quote_swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: &[u8],
+) -> ProgramResult {
+    let [pool, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    validate_instruction_length(instruction, QuoteSwapInstructionData::LEN)?;
+
+    let data: QuoteSwapInstructionData = bytemuck::checked::pod_read_unaligned(instruction);
+
+    validate_non_zero(data.amount_in)?;
+
+    let pool_data = pool.try_borrow_data()?;
+    let pool_state = Pool::load(&pool_data)?;
+
+    let is_a_to_b = match data.a_to_b {
+        0 => false,
+        1 => true,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    let (reserve_in, reserve_out) = if is_a_to_b {
+        (pool_state.reserve_a, pool_state.reserve_b)
+    } else {
+        (pool_state.reserve_b, pool_state.reserve_a)
+    };
+
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(AmmError::EmptyPool.into());
+    }
+
+    let (amount_out, fee) = pool_state.curve(Clock::get()?.unix_timestamp)?.swap_out(
+        data.amount_in,
+        reserve_in,
+        reserve_out,
+        pool_state.fee_rate,
+    )?;
+
+    let mut post_trade = *pool_state;
+    post_trade.apply_swap(is_a_to_b, data.amount_in, amount_out, fee)?;
+
+    set_return_data(bytemuck::bytes_of(&SwapQuote {
+        amount_out,
+        fee,
+        reserve_a: post_trade.reserve_a,
+        reserve_b: post_trade.reserve_b,
+    }));
+
+    Ok(())
+}
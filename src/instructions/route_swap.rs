@@ -0,0 +1,196 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{Sysvar, clock::Clock},
+};
+
+use super::{
+    deadline::{Deadline, validate_deadline},
+    swap::validate_swap_pool,
+    token::load_token_account,
+    utils::{receive_into, transfer_from_pool, transfer_from_user},
+    validators::{validate_non_zero, validate_pubkey_match, validate_signer},
+};
+use crate::{curve::SwapCurve, error::AmmError, states::Pool};
+
+/// Accounts per hop in the remaining accounts:
+/// `[pool, input_vault, output_vault, output_mint, output_token_program]`.
+///
+/// Every remaining account belongs to a hop, so no transfer hook accounts are
+/// forwarded and routes through transfer hook mints are not supported.
+pub const ACCOUNTS_PER_HOP: usize = 5;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct RouteSwapInstructionData {
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+}
+
+impl RouteSwapInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+pub fn process_route_swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instructions: &[u8],
+) -> ProgramResult {
+    let [
+        user,
+        user_input_account,
+        user_output_account,
+        input_mint,
+        input_token_program,
+        hops @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if hops.is_empty() || hops.len() % ACCOUNTS_PER_HOP != 0 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    validate_signer(user)?;
+
+    let (instructions, deadline) = Deadline::split(instructions, RouteSwapInstructionData::LEN)?;
+    validate_deadline(deadline)?;
+
+    let data: RouteSwapInstructionData = bytemuck::checked::pod_read_unaligned(instructions);
+
+    validate_non_zero(data.amount_in)?;
+
+    let output_mint = {
+        let user_input_acc = load_token_account(user_input_account)?;
+        let user_output_acc = load_token_account(user_output_account)?;
+
+        validate_pubkey_match(
+            user_input_acc.owner(),
+            user.key(),
+            AmmError::InvalidTokenAccountOwner,
+        )?;
+        validate_pubkey_match(
+            user_output_acc.owner(),
+            user.key(),
+            AmmError::InvalidTokenAccountOwner,
+        )?;
+
+        validate_pubkey_match(
+            user_input_acc.mint(),
+            input_mint.key(),
+            AmmError::InvalidMint,
+        )?;
+
+        *user_output_acc.mint()
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+    let mut amount = data.amount_in;
+    let mut mint = input_mint;
+    let mut token_program = input_token_program;
+    let mut previous_hop: Option<(&AccountInfo, &AccountInfo)> = None;
+
+    for hop in hops.chunks_exact(ACCOUNTS_PER_HOP) {
+        let [
+            pool,
+            input_vault,
+            output_vault,
+            hop_output_mint,
+            hop_output_token_program,
+        ] = hop
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let (reserve_in, reserve_out, fee_rate, is_a_to_b, curve) = {
+            let pool_data = pool.try_borrow_data()?;
+            let pool_state = Pool::load(&pool_data)?;
+
+            let (reserve_in, reserve_out, is_a_to_b) = validate_swap_pool(
+                program_id,
+                pool.key(),
+                pool_state,
+                mint.key(),
+                hop_output_mint.key(),
+                input_vault,
+                output_vault,
+                token_program,
+                hop_output_token_program,
+            )?;
+
+            (
+                reserve_in,
+                reserve_out,
+                pool_state.fee_rate,
+                is_a_to_b,
+                pool_state.curve(now)?,
+            )
+        };
+
+        // The previous hop pays straight into this hop's vault, so no
+        // intermediate token account is needed. Each hop is priced on what
+        // its vault received.
+        let amount_received = receive_into(input_vault, || match previous_hop {
+            None => transfer_from_user(
+                user,
+                user_input_account,
+                mint,
+                input_vault,
+                token_program,
+                amount,
+                &[],
+            ),
+            Some((previous_pool, previous_output_vault)) => transfer_from_pool(
+                previous_pool,
+                previous_output_vault,
+                mint,
+                input_vault,
+                token_program,
+                amount,
+                &[],
+            ),
+        })?;
+
+        let (amount_out, fee) =
+            curve.swap_out(amount_received, reserve_in, reserve_out, fee_rate)?;
+
+        if amount_out == 0 {
+            return Err(AmmError::ZeroOutput.into());
+        }
+
+        let mut pool_data = pool.try_borrow_mut_data()?;
+        let pool_state = Pool::load_mut(&mut pool_data)?;
+
+        pool_state.update_oracle(now)?;
+        pool_state.apply_swap(is_a_to_b, amount_received, amount_out, fee)?;
+
+        previous_hop = Some((pool, output_vault));
+        mint = hop_output_mint;
+        token_program = hop_output_token_program;
+        amount = amount_out;
+    }
+
+    validate_pubkey_match(mint.key(), &output_mint, AmmError::InvalidMint)?;
+
+    if amount < data.min_amount_out {
+        return Err(AmmError::SlippageExceeded.into());
+    }
+
+    let Some((last_pool, last_output_vault)) = previous_hop else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    transfer_from_pool(
+        last_pool,
+        last_output_vault,
+        mint,
+        user_output_account,
+        token_program,
+        amount,
+        &[],
+    )
+}
@@ -1,16 +1,22 @@
 use bytemuck::{Pod, Zeroable};
 use pinocchio::{
-    ProgramResult, account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey,
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{Sysvar, clock::Clock},
 };
-use pinocchio_token::{instructions::Transfer, state::TokenAccount};
 
 use super::{
-    utils::{create_pool_seed, create_pool_signer},
+    deadline::{Deadline, validate_deadline},
+    token::load_token_account,
+    utils::{receive_into, transfer_from_pool, transfer_from_user},
     validators::{
-        validate_instruction_length, validate_non_zero, validate_signer, validate_token_program,
+        validate_mint_token_program, validate_non_zero, validate_pool_status, validate_signer,
+        validate_vault,
     },
 };
-use crate::states::Pool;
+use crate::{constants::SWAPS_DISABLED, curve::SwapCurve, error::AmmError, states::Pool};
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
@@ -24,145 +30,248 @@ impl SwapInstructionData {
 }
 
 pub fn process_swap(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     instructions: &[u8],
 ) -> ProgramResult {
-    let [
-        user,
-        pool,
-        input_mint,
-        output_mint,
-        input_vault,
-        output_vault,
-        user_input_account,
-        user_output_account,
-        token_program,
-        _remaining @ ..,
-    ] = accounts
-    else {
-        return Err(ProgramError::InvalidAccountData);
-    };
+    let swap_accounts = SwapAccounts::parse(accounts)?;
 
-    validate_signer(user)?;
-    validate_token_program(token_program)?;
-    validate_instruction_length(instructions, SwapInstructionData::LEN)?;
+    validate_signer(swap_accounts.user)?;
+
+    let (instructions, deadline) = Deadline::split(instructions, SwapInstructionData::LEN)?;
+    validate_deadline(deadline)?;
 
     let data: SwapInstructionData = bytemuck::checked::pod_read_unaligned(instructions);
 
     validate_non_zero(data.amount_in)?;
 
-    let (amount_out, is_a_to_b, token_a, token_b, pool_bump) = {
-        let pool_state = pool.try_borrow_data()?;
+    let (reserve_in, reserve_out, fee_rate, is_a_to_b, curve) = {
+        let pool_state = swap_accounts.pool.try_borrow_data()?;
         let pool = Pool::load(&pool_state)?;
 
-        let user_input_acc = TokenAccount::from_account_info(user_input_account)?;
-        let user_output_acc = TokenAccount::from_account_info(user_output_account)?;
-
-        let (reserve_in, reserve_out, is_a_to_b) =
-            if input_mint.key() == &pool.token_a && output_mint.key() == &pool.token_b {
-                if input_vault.key() != &pool.vault_a {
-                    return Err(ProgramError::InvalidAccountData);
-                }
-
-                if output_vault.key() != &pool.vault_b {
-                    return Err(ProgramError::InvalidAccountData);
-                }
-                (pool.reserve_a, pool.reserve_b, true)
-            } else if input_mint.key() == &pool.token_b && output_mint.key() == &pool.token_a {
-                if input_vault.key() != &pool.vault_b {
-                    return Err(ProgramError::InvalidAccountData);
-                }
-
-                if output_vault.key() != &pool.vault_a {
-                    return Err(ProgramError::InvalidAccountData);
-                }
-                (pool.reserve_b, pool.reserve_a, false)
-            } else {
-                return Err(ProgramError::IllegalOwner);
-            };
-
-        if user_input_acc.mint() != input_mint.key() {
-            return Err(ProgramError::InvalidAccountData);
+        let (reserve_in, reserve_out, is_a_to_b) = swap_accounts.validate_pool(program_id, pool)?;
+
+        swap_accounts.validate_user_accounts()?;
+
+        (
+            reserve_in,
+            reserve_out,
+            pool.fee_rate,
+            is_a_to_b,
+            pool.curve(Clock::get()?.unix_timestamp)?,
+        )
+    };
+
+    // Price the trade on what the vault received, which is less than
+    // `amount_in` for mints that charge a fee on transfer.
+    let amount_received = swap_accounts.transfer_in(data.amount_in)?;
+
+    let (amount_out, fee) = curve.swap_out(amount_received, reserve_in, reserve_out, fee_rate)?;
+
+    if amount_out == 0 {
+        return Err(AmmError::ZeroOutput.into());
+    }
+
+    if amount_out < data.min_amount_out {
+        return Err(AmmError::SlippageExceeded.into());
+    }
+
+    swap_accounts.settle(amount_received, amount_out, fee, is_a_to_b)
+}
+
+/// Accounts shared by `Swap` and `SwapExactOut`.
+pub(super) struct SwapAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub pool: &'a AccountInfo,
+    pub input_mint: &'a AccountInfo,
+    pub output_mint: &'a AccountInfo,
+    pub input_vault: &'a AccountInfo,
+    pub output_vault: &'a AccountInfo,
+    pub user_input_account: &'a AccountInfo,
+    pub user_output_account: &'a AccountInfo,
+    pub input_token_program: &'a AccountInfo,
+    pub output_token_program: &'a AccountInfo,
+    /// Transfer hook accounts of either mint, forwarded on every transfer.
+    pub hook_accounts: &'a [AccountInfo],
+}
+
+impl<'a> SwapAccounts<'a> {
+    pub fn parse(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [
+            user,
+            pool,
+            input_mint,
+            output_mint,
+            input_vault,
+            output_vault,
+            user_input_account,
+            user_output_account,
+            input_token_program,
+            output_token_program,
+            hook_accounts @ ..,
+        ] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            user,
+            pool,
+            input_mint,
+            output_mint,
+            input_vault,
+            output_vault,
+            user_input_account,
+            user_output_account,
+            input_token_program,
+            output_token_program,
+            hook_accounts,
+        })
+    }
+
+    /// See [`validate_swap_pool`].
+    pub fn validate_pool(
+        &self,
+        program_id: &Pubkey,
+        pool: &Pool,
+    ) -> Result<(u64, u64, bool), ProgramError> {
+        validate_swap_pool(
+            program_id,
+            self.pool.key(),
+            pool,
+            self.input_mint.key(),
+            self.output_mint.key(),
+            self.input_vault,
+            self.output_vault,
+            self.input_token_program,
+            self.output_token_program,
+        )
+    }
+
+    pub fn validate_user_accounts(&self) -> ProgramResult {
+        let user_input_acc = load_token_account(self.user_input_account)?;
+        let user_output_acc = load_token_account(self.user_output_account)?;
+
+        if user_input_acc.mint() != self.input_mint.key() {
+            return Err(AmmError::InvalidMint.into());
         }
-        if user_output_acc.mint() != output_mint.key() {
-            return Err(ProgramError::InvalidAccountData);
+        if user_output_acc.mint() != self.output_mint.key() {
+            return Err(AmmError::InvalidMint.into());
         }
 
-        if user_input_acc.owner() != user.key() {
-            return Err(ProgramError::InvalidAccountData);
+        if user_input_acc.owner() != self.user.key() {
+            return Err(AmmError::InvalidTokenAccountOwner.into());
         }
-        if user_output_acc.owner() != user.key() {
-            return Err(ProgramError::InvalidAccountData);
+        if user_output_acc.owner() != self.user.key() {
+            return Err(AmmError::InvalidTokenAccountOwner.into());
         }
-        let amount_in_with_fee = data
-            .amount_in
-            .checked_mul(
-                10000_u64
-                    .checked_sub(pool.fee_rate as u64)
-                    .ok_or(ProgramError::ArithmeticOverflow)?,
-            )
-            .ok_or(ProgramError::ArithmeticOverflow)?
-            .checked_div(10000)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-
-        let amount_out = reserve_out
-            .checked_mul(amount_in_with_fee)
-            .ok_or(ProgramError::ArithmeticOverflow)?
-            .checked_div(
-                reserve_in
-                    .checked_add(amount_in_with_fee)
-                    .ok_or(ProgramError::ArithmeticOverflow)?,
+        Ok(())
+    }
+
+    /// Moves `amount_in` from the user into the input vault and returns the
+    /// amount the vault received.
+    pub fn transfer_in(&self, amount_in: u64) -> Result<u64, ProgramError> {
+        receive_into(self.input_vault, || {
+            transfer_from_user(
+                self.user,
+                self.user_input_account,
+                self.input_mint,
+                self.input_vault,
+                self.input_token_program,
+                amount_in,
+                self.hook_accounts,
             )
-            .ok_or(ProgramError::ArithmeticOverflow)?;
+        })
+    }
 
-        if amount_out < data.min_amount_out {
-            return Err(ProgramError::InsufficientFunds);
-        }
+    /// Pays `amount_out` from the pool to the user and records the trade,
+    /// with `amount_in` as received by the vault, in the reserves.
+    pub fn settle(
+        &self,
+        amount_in: u64,
+        amount_out: u64,
+        fee: u64,
+        is_a_to_b: bool,
+    ) -> ProgramResult {
+        transfer_from_pool(
+            self.pool,
+            self.output_vault,
+            self.output_mint,
+            self.user_output_account,
+            self.output_token_program,
+            amount_out,
+            self.hook_accounts,
+        )?;
 
-        (amount_out, is_a_to_b, pool.token_a, pool.token_b, pool.bump)
-    };
+        let mut pool_data = self.pool.try_borrow_mut_data()?;
+        let pool_state = Pool::load_mut(&mut pool_data)?;
 
-    Transfer {
-        from: user_input_account,
-        to: input_vault,
-        amount: data.amount_in,
-        authority: user,
+        pool_state.update_oracle(Clock::get()?.unix_timestamp)?;
+        pool_state.apply_swap(is_a_to_b, amount_in, amount_out, fee)
     }
-    .invoke()?;
-
-    let binding = [pool_bump];
-    let pool_seed = create_pool_seed(&binding, &token_a, &token_b);
-    Transfer {
-        from: output_vault,
-        to: user_output_account,
-        amount: amount_out,
-        authority: pool,
-    }
-    .invoke_signed(&[create_pool_signer(&pool_seed)])?;
-
-    let mut pool_data = pool.try_borrow_mut_data()?;
-    let pool_state = Pool::load_mut(&mut pool_data)?;
-
-    if is_a_to_b {
-        pool_state.reserve_a = pool_state
-            .reserve_a
-            .checked_add(data.amount_in)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-        pool_state.reserve_b = pool_state
-            .reserve_b
-            .checked_sub(amount_out)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-    } else {
-        pool_state.reserve_b = pool_state
-            .reserve_b
-            .checked_add(data.amount_in)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-        pool_state.reserve_a = pool_state
-            .reserve_a
-            .checked_sub(amount_out)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
+}
+
+/// Checks that swaps are enabled, that the mint pair, token programs and vault
+/// PDAs belong to `pool` and that the pool holds liquidity on both sides.
+///
+/// Returns `(reserve_in, reserve_out, is_a_to_b)`.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn validate_swap_pool(
+    program_id: &Pubkey,
+    pool_key: &Pubkey,
+    pool: &Pool,
+    input_mint: &Pubkey,
+    output_mint: &Pubkey,
+    input_vault: &AccountInfo,
+    output_vault: &AccountInfo,
+    input_token_program: &AccountInfo,
+    output_token_program: &AccountInfo,
+) -> Result<(u64, u64, bool), ProgramError> {
+    validate_pool_status(pool, SWAPS_DISABLED, AmmError::SwapsDisabled)?;
+
+    let (reserve_in, reserve_out, is_a_to_b, input_vault_bump, output_vault_bump) =
+        if input_mint == &pool.token_a && output_mint == &pool.token_b {
+            validate_mint_token_program(input_token_program, &pool.token_program_a)?;
+            validate_mint_token_program(output_token_program, &pool.token_program_b)?;
+            (
+                pool.reserve_a,
+                pool.reserve_b,
+                true,
+                pool.vault_a_bump,
+                pool.vault_b_bump,
+            )
+        } else if input_mint == &pool.token_b && output_mint == &pool.token_a {
+            validate_mint_token_program(input_token_program, &pool.token_program_b)?;
+            validate_mint_token_program(output_token_program, &pool.token_program_a)?;
+            (
+                pool.reserve_b,
+                pool.reserve_a,
+                false,
+                pool.vault_b_bump,
+                pool.vault_a_bump,
+            )
+        } else {
+            return Err(AmmError::InvalidMint.into());
+        };
+
+    validate_vault(
+        program_id,
+        pool_key,
+        input_vault.key(),
+        input_mint,
+        input_vault_bump,
+    )?;
+    validate_vault(
+        program_id,
+        pool_key,
+        output_vault.key(),
+        output_mint,
+        output_vault_bump,
+    )?;
+
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(AmmError::EmptyPool.into());
     }
 
-    Ok(())
+    Ok((reserve_in, reserve_out, is_a_to_b))
 }
@@ -0,0 +1,73 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    pubkey::Pubkey,
+    sysvars::{Sysvar, clock::Clock},
+};
+
+use super::{
+    deadline::{Deadline, validate_deadline},
+    swap::SwapAccounts,
+    validators::{validate_non_zero, validate_signer},
+};
+use crate::{curve::SwapCurve, error::AmmError, states::Pool};
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct SwapExactOutInstructionData {
+    pub amount_out: u64,
+    pub max_amount_in: u64,
+}
+
+impl SwapExactOutInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+pub fn process_swap_exact_out(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instructions: &[u8],
+) -> ProgramResult {
+    let swap_accounts = SwapAccounts::parse(accounts)?;
+
+    validate_signer(swap_accounts.user)?;
+
+    let (instructions, deadline) = Deadline::split(instructions, SwapExactOutInstructionData::LEN)?;
+    validate_deadline(deadline)?;
+
+    let data: SwapExactOutInstructionData = bytemuck::checked::pod_read_unaligned(instructions);
+
+    validate_non_zero(data.amount_out)?;
+
+    let (amount_in, fee, is_a_to_b) = {
+        let pool_state = swap_accounts.pool.try_borrow_data()?;
+        let pool = Pool::load(&pool_state)?;
+
+        let (reserve_in, reserve_out, is_a_to_b) = swap_accounts.validate_pool(program_id, pool)?;
+
+        swap_accounts.validate_user_accounts()?;
+
+        let (amount_in, fee) = pool.curve(Clock::get()?.unix_timestamp)?.swap_in(
+            data.amount_out,
+            reserve_in,
+            reserve_out,
+            pool.fee_rate,
+        )?;
+
+        if amount_in > data.max_amount_in {
+            return Err(AmmError::SlippageExceeded.into());
+        }
+
+        (amount_in, fee, is_a_to_b)
+    };
+
+    // The curve needs all of `amount_in`; a fee on transfer would leave the
+    // pool short.
+    let amount_received = swap_accounts.transfer_in(amount_in)?;
+    if amount_received < amount_in {
+        return Err(AmmError::InsufficientAmountReceived.into());
+    }
+
+    swap_accounts.settle(amount_received, data.amount_out, fee, is_a_to_b)
+}
@@ -0,0 +1,183 @@
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{Sysvar, clock::Clock},
+};
+
+use pinocchio_token::state::Mint;
+
+use super::{
+    token::load_token_account,
+    utils::transfer_from_pool,
+    validators::{validate_mint_token_program, validate_pubkey_match, validate_vault},
+};
+use crate::{error::AmmError, states::Pool};
+
+/// Sets the reserves to the vault balances, less the protocol fees the vaults
+/// hold. Anyone may call it, e.g. after tokens were donated to a vault.
+///
+/// Unlike Uniswap V2 it fails on a pool without LP supply. Donations synced
+/// into such a pool would set its price before the first deposit, or leave
+/// one reserve at zero, where no deposit or swap can ever succeed.
+pub fn process_sync(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction: &[u8],
+) -> ProgramResult {
+    let [pool, lp_mint, vault_a, vault_b, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let mut pool_data = pool.try_borrow_mut_data()?;
+    let pool_state = Pool::load_mut(&mut pool_data)?;
+
+    validate_pubkey_match(lp_mint.key(), &pool_state.lp_mint, AmmError::InvalidLpMint)?;
+    if Mint::from_account_info(lp_mint)?.supply() == 0 {
+        return Err(AmmError::EmptyPool.into());
+    }
+
+    let (balance_a, balance_b) =
+        pooled_balances(program_id, pool.key(), pool_state, vault_a, vault_b)?;
+
+    pool_state.update_oracle(Clock::get()?.unix_timestamp)?;
+    pool_state.reserve_a = balance_a;
+    pool_state.reserve_b = balance_b;
+
+    Ok(())
+}
+
+/// Sends whatever the vaults hold above the reserves and protocol fees to the
+/// caller-supplied token accounts. The reserves are left unchanged.
+pub fn process_skim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction: &[u8],
+) -> ProgramResult {
+    let [
+        pool,
+        vault_a,
+        vault_b,
+        to_token_a,
+        to_token_b,
+        mint_a,
+        mint_b,
+        token_program_a,
+        token_program_b,
+        hook_accounts @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let (excess_a, excess_b) = {
+        let pool_data = pool.try_borrow_data()?;
+        let pool_state = Pool::load(&pool_data)?;
+
+        validate_pubkey_match(mint_a.key(), &pool_state.token_a, AmmError::InvalidMint)?;
+        validate_pubkey_match(mint_b.key(), &pool_state.token_b, AmmError::InvalidMint)?;
+        validate_mint_token_program(token_program_a, &pool_state.token_program_a)?;
+        validate_mint_token_program(token_program_b, &pool_state.token_program_b)?;
+        validate_pubkey_match(
+            load_token_account(to_token_a)?.mint(),
+            &pool_state.token_a,
+            AmmError::InvalidMint,
+        )?;
+        validate_pubkey_match(
+            load_token_account(to_token_b)?.mint(),
+            &pool_state.token_b,
+            AmmError::InvalidMint,
+        )?;
+
+        let (balance_a, balance_b) =
+            pooled_balances(program_id, pool.key(), pool_state, vault_a, vault_b)?;
+
+        (
+            balance_a
+                .checked_sub(pool_state.reserve_a)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+            balance_b
+                .checked_sub(pool_state.reserve_b)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        )
+    };
+
+    if excess_a > 0 {
+        transfer_from_pool(
+            pool,
+            vault_a,
+            mint_a,
+            to_token_a,
+            token_program_a,
+            excess_a,
+            hook_accounts,
+        )?;
+    }
+
+    if excess_b > 0 {
+        transfer_from_pool(
+            pool,
+            vault_b,
+            mint_b,
+            to_token_b,
+            token_program_b,
+            excess_b,
+            hook_accounts,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Returns the vault balances less the protocol fees held in them, after
+/// checking the vault PDAs of the pool.
+///
+/// Fails while a flash loan is outstanding: the lent tokens are missing from
+/// the vault, so syncing would write the loan off the reserves and leave the
+/// repayment to be skimmed.
+fn pooled_balances(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    pool_state: &Pool,
+    vault_a: &AccountInfo,
+    vault_b: &AccountInfo,
+) -> Result<(u64, u64), ProgramError> {
+    validate_vault(
+        program_id,
+        pool,
+        vault_a.key(),
+        &pool_state.token_a,
+        pool_state.vault_a_bump,
+    )?;
+    validate_vault(
+        program_id,
+        pool,
+        vault_b.key(),
+        &pool_state.token_b,
+        pool_state.vault_b_bump,
+    )?;
+
+    if pool_state.flash_loan_a != 0 || pool_state.flash_loan_b != 0 {
+        return Err(AmmError::FlashLoanActive.into());
+    }
+
+    let balance_a = load_token_account(vault_a)?
+        .amount()
+        .checked_sub(pool_state.protocol_fees_a)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let balance_b = load_token_account(vault_b)?
+        .amount()
+        .checked_sub(pool_state.protocol_fees_b)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok((balance_a, balance_b))
+}
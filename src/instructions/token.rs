@@ -0,0 +1,93 @@
+use pinocchio::{
+    ProgramResult,
+    account_info::{AccountInfo, Ref},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use pinocchio_token::state::{Mint, TokenAccount};
+
+use crate::error::AmmError;
+
+/// Token-2022 writes an account type byte right after the base token account
+/// layout once a mint or token account carries extensions, followed by the
+/// extension TLV entries.
+const ACCOUNT_TYPE_OFFSET: usize = TokenAccount::LEN;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// Size of a multisig account, which is never a mint or token account.
+const MULTISIG_LEN: usize = 355;
+
+/// Token-2022 mint extensions a pool cannot hold safely:
+///
+/// - `9` NonTransferable: tokens could never leave the vaults.
+/// - `12` PermanentDelegate: the delegate could move tokens out of the vaults.
+const UNSUPPORTED_MINT_EXTENSIONS: [u16; 2] = [9, 12];
+
+/// Returns the token program that owns `account`.
+pub fn token_program_of(account: &AccountInfo) -> Result<Pubkey, ProgramError> {
+    if account.is_owned_by(&pinocchio_token::ID) {
+        Ok(pinocchio_token::ID)
+    } else if account.is_owned_by(&pinocchio_token_2022::ID) {
+        Ok(pinocchio_token_2022::ID)
+    } else {
+        Err(ProgramError::InvalidAccountOwner)
+    }
+}
+
+/// Checks that `account` holds a `base_len` layout of either token program,
+/// allowing trailing Token-2022 extensions for the given account type.
+fn validate_layout(account: &AccountInfo, base_len: usize, account_type: u8) -> ProgramResult {
+    let token_program = token_program_of(account)?;
+    let data = account.try_borrow_data()?;
+
+    let valid = data.len() == base_len
+        || (token_program == pinocchio_token_2022::ID
+            && data.len() > ACCOUNT_TYPE_OFFSET
+            && data.len() != MULTISIG_LEN
+            && data[ACCOUNT_TYPE_OFFSET] == account_type);
+
+    if !valid {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Loads a mint owned by either token program.
+pub fn load_mint(mint: &AccountInfo) -> Result<Ref<'_, Mint>, ProgramError> {
+    validate_layout(mint, Mint::LEN, ACCOUNT_TYPE_MINT)?;
+
+    // SAFETY: the layout check above guarantees the base mint layout.
+    Ok(Ref::map(mint.try_borrow_data()?, |data| unsafe {
+        Mint::from_bytes_unchecked(data)
+    }))
+}
+
+/// Loads a token account owned by either token program.
+pub fn load_token_account(account: &AccountInfo) -> Result<Ref<'_, TokenAccount>, ProgramError> {
+    validate_layout(account, TokenAccount::LEN, ACCOUNT_TYPE_ACCOUNT)?;
+
+    // SAFETY: the layout check above guarantees the base token account layout.
+    Ok(Ref::map(account.try_borrow_data()?, |data| unsafe {
+        TokenAccount::from_bytes_unchecked(data)
+    }))
+}
+
+/// Rejects Token-2022 mints carrying an extension in `UNSUPPORTED_MINT_EXTENSIONS`.
+pub fn validate_mint_extensions(mint: &AccountInfo) -> ProgramResult {
+    let data = mint.try_borrow_data()?;
+
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let extension = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+
+        if UNSUPPORTED_MINT_EXTENSIONS.contains(&extension) {
+            return Err(AmmError::UnsupportedMintExtension.into());
+        }
+
+        offset += 4 + length;
+    }
+
+    Ok(())
+}
@@ -1,34 +1,262 @@
-use crate::{constants::POOL_SEED, states::Pool};
+use crate::{
+    constants::{MAX_TRANSFER_HOOK_ACCOUNTS, POOL_SEED, VAULT_SEED},
+    states::Pool,
+};
 use pinocchio::{
+    ProgramResult,
     account_info::AccountInfo,
-    instruction::{Seed, Signer},
+    cpi::{get_return_data, invoke, invoke_signed_with_bounds},
+    instruction::{AccountMeta, Instruction, Seed, Signer},
     program_error::ProgramError,
     pubkey::Pubkey,
+    sysvars::rent::Rent,
 };
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token_2022::instructions::{InitializeAccount3, TransferChecked};
+
+use super::token::{load_mint, load_token_account};
 
 pub fn create_pool_seed<'a>(
     pool_bump: &'a [u8; 1],
     token_a: &'a Pubkey,
     token_b: &'a Pubkey,
-) -> [Seed<'a>; 4] {
+    curve_type: &'a [u8; 1],
+) -> [Seed<'a>; 5] {
     [
         Seed::from(POOL_SEED.as_bytes()),
         Seed::from(token_a.as_ref()),
         Seed::from(token_b.as_ref()),
+        Seed::from(curve_type.as_ref()),
         Seed::from(pool_bump.as_ref()),
     ]
 }
 
-pub fn load_pool_data(pool: &AccountInfo) -> Result<(u8, Pubkey, Pubkey), ProgramError> {
+pub fn create_vault_seed<'a>(
+    vault_bump: &'a [u8; 1],
+    pool: &'a Pubkey,
+    mint: &'a Pubkey,
+) -> [Seed<'a>; 4] {
+    [
+        Seed::from(VAULT_SEED.as_bytes()),
+        Seed::from(pool.as_ref()),
+        Seed::from(mint.as_ref()),
+        Seed::from(vault_bump.as_ref()),
+    ]
+}
+
+pub fn load_pool_data(pool: &AccountInfo) -> Result<(u8, Pubkey, Pubkey, u8), ProgramError> {
     let pool_data = pool.try_borrow_data()?;
     let pool_state = Pool::load(&pool_data)?;
     Ok((
         pool_state.bump,
         Pubkey::from(pool_state.token_a),
         Pubkey::from(pool_state.token_b),
+        pool_state.curve_type,
     ))
 }
 
-pub fn create_pool_signer<'a, 'b>(pool_seed: &'a [Seed<'b>; 4]) -> Signer<'a, 'b> {
+pub fn create_pool_signer<'a, 'b>(pool_seed: &'a [Seed<'b>; 5]) -> Signer<'a, 'b> {
     Signer::from(&pool_seed[..])
 }
+
+/// Creates `vault` at its PDA as a token account for `mint` owned by the pool.
+///
+/// The vault is sized by the token program, so Token-2022 mints get room for
+/// the account extensions they require.
+pub fn create_vault(
+    payer: &AccountInfo,
+    pool: &AccountInfo,
+    vault: &AccountInfo,
+    mint: &AccountInfo,
+    token_program: &AccountInfo,
+    vault_bump: u8,
+    rent: &Rent,
+) -> ProgramResult {
+    let space = token_account_size(mint, token_program)?;
+
+    let binding = [vault_bump];
+    let vault_seed = create_vault_seed(&binding, pool.key(), mint.key());
+
+    CreateAccount {
+        from: payer,
+        to: vault,
+        space,
+        lamports: rent.minimum_balance(space as usize),
+        owner: token_program.key(),
+    }
+    .invoke_signed(&[Signer::from(&vault_seed[..])])?;
+
+    InitializeAccount3 {
+        account: vault,
+        mint,
+        owner: pool.key(),
+        token_program: token_program.key(),
+    }
+    .invoke()
+}
+
+/// Asks the token program how large a token account for `mint` must be,
+/// through `GetAccountDataSize`.
+fn token_account_size(
+    mint: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<u64, ProgramError> {
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &[AccountMeta::readonly(mint.key())],
+        data: &[21],
+    };
+    invoke(&instruction, &[mint])?;
+
+    let return_data = get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+    if return_data.program_id() != token_program.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let size: [u8; 8] = return_data
+        .as_slice()
+        .try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    Ok(u64::from_le_bytes(size))
+}
+
+/// Accounts of a `TransferChecked` CPI: source, mint, destination and
+/// authority, followed by the forwarded transfer hook accounts.
+const MAX_TRANSFER_ACCOUNTS: usize = 4 + MAX_TRANSFER_HOOK_ACCOUNTS;
+
+/// Transfers `amount` of `mint` with `TransferChecked`, using the mint's
+/// decimals and whichever token program owns it.
+///
+/// `hook_accounts` are appended to the CPI as-is. Token-2022 looks up the
+/// hook program, its validation account and the extra accounts among them by
+/// key, so the hook accounts of both pool sides can be forwarded together.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked(
+    from: &AccountInfo,
+    mint: &AccountInfo,
+    to: &AccountInfo,
+    authority: &AccountInfo,
+    token_program: &AccountInfo,
+    amount: u64,
+    hook_accounts: &[AccountInfo],
+    signers: &[Signer],
+) -> ProgramResult {
+    let decimals = load_mint(mint)?.decimals();
+
+    if hook_accounts.is_empty() {
+        return TransferChecked {
+            from,
+            mint,
+            to,
+            authority,
+            amount,
+            decimals,
+            token_program: token_program.key(),
+        }
+        .invoke_signed(signers);
+    }
+
+    if hook_accounts.len() > MAX_TRANSFER_HOOK_ACCOUNTS {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let account_infos: [&AccountInfo; MAX_TRANSFER_ACCOUNTS] = core::array::from_fn(|i| match i {
+        0 => from,
+        1 => mint,
+        2 => to,
+        3 => authority,
+        _ => hook_accounts.get(i - 4).unwrap_or(from),
+    });
+
+    let account_metas: [AccountMeta; MAX_TRANSFER_ACCOUNTS] = core::array::from_fn(|i| match i {
+        0 => AccountMeta::writable(from.key()),
+        1 => AccountMeta::readonly(mint.key()),
+        2 => AccountMeta::writable(to.key()),
+        3 => AccountMeta::readonly_signer(authority.key()),
+        _ => {
+            let account = account_infos[i];
+            AccountMeta::new(account.key(), account.is_writable(), account.is_signer())
+        }
+    });
+
+    // [discriminator (12), amount (u64), decimals (u8)]
+    let mut data = [0u8; 10];
+    data[0] = 12;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+    data[9] = decimals;
+
+    let len = 4 + hook_accounts.len();
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &account_metas[..len],
+        data: &data,
+    };
+
+    invoke_signed_with_bounds::<MAX_TRANSFER_ACCOUNTS>(&instruction, &account_infos[..len], signers)
+}
+
+/// Transfers `amount` out of a user token account, signed by the user.
+pub fn transfer_from_user(
+    user: &AccountInfo,
+    from: &AccountInfo,
+    mint: &AccountInfo,
+    to: &AccountInfo,
+    token_program: &AccountInfo,
+    amount: u64,
+    hook_accounts: &[AccountInfo],
+) -> ProgramResult {
+    transfer_checked(
+        from,
+        mint,
+        to,
+        user,
+        token_program,
+        amount,
+        hook_accounts,
+        &[],
+    )
+}
+
+/// Transfers `amount` out of a pool vault, signing as the pool PDA.
+pub fn transfer_from_pool(
+    pool: &AccountInfo,
+    vault: &AccountInfo,
+    mint: &AccountInfo,
+    to: &AccountInfo,
+    token_program: &AccountInfo,
+    amount: u64,
+    hook_accounts: &[AccountInfo],
+) -> ProgramResult {
+    let (pool_bump, token_a, token_b, curve_type) = load_pool_data(pool)?;
+    let binding = [pool_bump];
+    let curve_binding = [curve_type];
+    let pool_seed = create_pool_seed(&binding, &token_a, &token_b, &curve_binding);
+
+    transfer_checked(
+        vault,
+        mint,
+        to,
+        pool,
+        token_program,
+        amount,
+        hook_accounts,
+        &[create_pool_signer(&pool_seed)],
+    )
+}
+
+/// Runs `transfer` into `vault` and returns how much the vault balance grew.
+///
+/// For mints that charge a fee on transfer this is less than the amount sent,
+/// so reserves must be credited with the returned value.
+pub fn receive_into(
+    vault: &AccountInfo,
+    transfer: impl FnOnce() -> ProgramResult,
+) -> Result<u64, ProgramError> {
+    let before = load_token_account(vault)?.amount();
+    transfer()?;
+    let after = load_token_account(vault)?.amount();
+
+    after
+        .checked_sub(before)
+        .ok_or(ProgramError::ArithmeticOverflow)
+}
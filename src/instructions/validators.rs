@@ -1,8 +1,17 @@
 use pinocchio::{
-    ProgramResult, account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey,
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::{Pubkey, create_program_address},
 };
 use pinocchio_token::ID;
 
+use crate::{
+    constants::{CONFIG_SEED, VAULT_SEED},
+    error::AmmError,
+    states::{Pool, ProtocolConfig},
+};
+
 pub fn validate_signer(account: &AccountInfo) -> ProgramResult {
     if !account.is_signer() {
         return Err(ProgramError::MissingRequiredSignature);
@@ -17,6 +26,17 @@ pub fn validate_token_program(token_program: &AccountInfo) -> ProgramResult {
     Ok(())
 }
 
+/// Checks that `token_program` is the program recorded in the pool for a mint.
+pub fn validate_mint_token_program(
+    token_program: &AccountInfo,
+    expected: &Pubkey,
+) -> ProgramResult {
+    if token_program.key() != expected {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
 pub fn validate_instruction_length(instruction: &[u8], expected_len: usize) -> ProgramResult {
     if instruction.len() != expected_len {
         return Err(ProgramError::InvalidInstructionData);
@@ -24,16 +44,94 @@ pub fn validate_instruction_length(instruction: &[u8], expected_len: usize) -> P
     Ok(())
 }
 
-pub fn validate_pubkey_match(actual: &Pubkey, expected: &Pubkey) -> ProgramResult {
+pub fn validate_pubkey_match(actual: &Pubkey, expected: &Pubkey, error: AmmError) -> ProgramResult {
     if actual != expected {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Checks that `authority` signed and is the authority recorded in the pool.
+pub fn validate_authority(authority: &AccountInfo, pool_state: &Pool) -> ProgramResult {
+    validate_signer(authority)?;
+    validate_pubkey_match(
+        authority.key(),
+        &pool_state.authority,
+        AmmError::InvalidAuthority,
+    )
+}
+
+/// Fails with `error` if the pool has the `disabled` status bit set.
+pub fn validate_pool_status(pool_state: &Pool, disabled: u8, error: AmmError) -> ProgramResult {
+    if pool_state.status & disabled != 0 {
+        return Err(error.into());
     }
     Ok(())
 }
 
+/// Checks that `vault` is the vault PDA of `pool` for `mint`, derived with the
+/// bump stored in the pool.
+pub fn validate_vault(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    vault: &Pubkey,
+    mint: &Pubkey,
+    bump: u8,
+) -> ProgramResult {
+    let expected = create_program_address(
+        &[VAULT_SEED.as_bytes(), pool.as_ref(), mint.as_ref(), &[bump]],
+        program_id,
+    )?;
+
+    validate_pubkey_match(vault, &expected, AmmError::InvalidVault)
+}
+
+/// Checks `vault` and `token_program` against the side of the pool that holds
+/// `mint`, returning `true` for token A and `false` for token B.
+pub fn validate_pool_side(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    pool_state: &Pool,
+    vault: &AccountInfo,
+    mint: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<bool, ProgramError> {
+    let (is_a, vault_bump, expected_token_program) = if mint.key() == &pool_state.token_a {
+        (true, pool_state.vault_a_bump, &pool_state.token_program_a)
+    } else if mint.key() == &pool_state.token_b {
+        (false, pool_state.vault_b_bump, &pool_state.token_program_b)
+    } else {
+        return Err(AmmError::InvalidMint.into());
+    };
+
+    validate_vault(program_id, pool, vault.key(), mint.key(), vault_bump)?;
+    validate_mint_token_program(token_program, expected_token_program)?;
+
+    Ok(is_a)
+}
+
 pub fn validate_non_zero(amount: u64) -> ProgramResult {
     if amount == 0 {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(AmmError::ZeroAmount.into());
     }
     Ok(())
 }
+
+/// Loads the protocol config, checking that `config` is the program's config PDA.
+pub fn validate_config(
+    program_id: &Pubkey,
+    config: &AccountInfo,
+) -> Result<ProtocolConfig, ProgramError> {
+    if !config.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let config_data = config.try_borrow_data()?;
+    let config_state = *ProtocolConfig::load(&config_data)?;
+
+    let expected =
+        create_program_address(&[CONFIG_SEED.as_bytes(), &[config_state.bump]], program_id)?;
+    validate_pubkey_match(config.key(), &expected, AmmError::InvalidConfigAddress)?;
+
+    Ok(config_state)
+}
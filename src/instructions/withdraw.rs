@@ -1,20 +1,31 @@
 use bytemuck::{Pod, Zeroable};
 use pinocchio::{
-    ProgramResult, account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey,
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{Sysvar, clock::Clock},
 };
 use pinocchio_token::{
-    instructions::{Burn, Transfer},
+    instructions::Burn,
     state::{Mint, TokenAccount},
 };
 
 use super::{
-    utils::{create_pool_seed, create_pool_signer, load_pool_data},
+    deadline::{Deadline, validate_deadline},
+    token::load_token_account,
+    utils::transfer_from_pool,
     validators::{
-        validate_instruction_length, validate_non_zero, validate_pubkey_match, validate_signer,
-        validate_token_program,
+        validate_mint_token_program, validate_non_zero, validate_pool_status,
+        validate_pubkey_match, validate_signer, validate_token_program, validate_vault,
     },
 };
-use crate::states::Pool;
+use crate::{
+    constants::{MINIMUM_LIQUIDITY, WITHDRAWALS_DISABLED},
+    curve::SwapCurve,
+    error::AmmError,
+    states::Pool,
+};
 
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -29,7 +40,7 @@ impl WithdrawInstructionData {
 }
 
 pub fn process_withdraw(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     instructions: &[u8],
 ) -> ProgramResult {
@@ -43,79 +54,131 @@ pub fn process_withdraw(
         user_token_a,
         user_token_b,
         token_program,
-        _remaining @ ..,
+        mint_a,
+        mint_b,
+        token_program_a,
+        token_program_b,
+        hook_accounts @ ..,
     ] = accounts
     else {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::NotEnoughAccountKeys);
     };
 
     validate_signer(user)?;
     validate_token_program(token_program)?;
-    validate_instruction_length(instructions, WithdrawInstructionData::LEN)?;
+
+    let (instructions, deadline) = Deadline::split(instructions, WithdrawInstructionData::LEN)?;
+    validate_deadline(deadline)?;
 
     let data: WithdrawInstructionData = bytemuck::checked::pod_read_unaligned(instructions);
 
     validate_non_zero(data.amount_in)?;
 
     let (amount_a_out, amount_b_out) = {
-        let pool = pool.try_borrow_data()?;
-        let pool_state = Pool::load(&pool)?;
+        let pool_data = pool.try_borrow_data()?;
+        let pool_state = Pool::load(&pool_data)?;
 
         let lp_mint_acc = Mint::from_account_info(lp_mint)?;
 
         let user_lp_acc = TokenAccount::from_account_info(user_lp_token)?;
-        let user_token_a_acc = TokenAccount::from_account_info(user_token_a)?;
-        let user_token_b_acc = TokenAccount::from_account_info(user_token_b)?;
-
-        validate_pubkey_match(user_lp_acc.mint(), &pool_state.lp_mint)?;
-        validate_pubkey_match(user_token_a_acc.mint(), &pool_state.token_a)?;
-        validate_pubkey_match(user_token_b_acc.mint(), &pool_state.token_b)?;
-        validate_pubkey_match(user_lp_acc.owner(), user.key())?;
-        validate_pubkey_match(user_token_a_acc.owner(), user.key())?;
-        validate_pubkey_match(user_token_b_acc.owner(), user.key())?;
-        validate_pubkey_match(lp_mint.key(), &pool_state.lp_mint)?;
-        validate_pubkey_match(vault_a.key(), &pool_state.vault_a)?;
-        validate_pubkey_match(vault_b.key(), &pool_state.vault_b)?;
+        let user_token_a_acc = load_token_account(user_token_a)?;
+        let user_token_b_acc = load_token_account(user_token_b)?;
+
+        validate_pool_status(
+            pool_state,
+            WITHDRAWALS_DISABLED,
+            AmmError::WithdrawalsDisabled,
+        )?;
+        validate_pubkey_match(
+            user_lp_acc.mint(),
+            &pool_state.lp_mint,
+            AmmError::InvalidLpMint,
+        )?;
+        validate_pubkey_match(
+            user_token_a_acc.mint(),
+            &pool_state.token_a,
+            AmmError::InvalidMint,
+        )?;
+        validate_pubkey_match(
+            user_token_b_acc.mint(),
+            &pool_state.token_b,
+            AmmError::InvalidMint,
+        )?;
+        validate_pubkey_match(
+            user_lp_acc.owner(),
+            user.key(),
+            AmmError::InvalidTokenAccountOwner,
+        )?;
+        validate_pubkey_match(
+            user_token_a_acc.owner(),
+            user.key(),
+            AmmError::InvalidTokenAccountOwner,
+        )?;
+        validate_pubkey_match(
+            user_token_b_acc.owner(),
+            user.key(),
+            AmmError::InvalidTokenAccountOwner,
+        )?;
+        validate_pubkey_match(lp_mint.key(), &pool_state.lp_mint, AmmError::InvalidLpMint)?;
+        validate_vault(
+            program_id,
+            pool.key(),
+            vault_a.key(),
+            &pool_state.token_a,
+            pool_state.vault_a_bump,
+        )?;
+        validate_vault(
+            program_id,
+            pool.key(),
+            vault_b.key(),
+            &pool_state.token_b,
+            pool_state.vault_b_bump,
+        )?;
+        validate_pubkey_match(mint_a.key(), &pool_state.token_a, AmmError::InvalidMint)?;
+        validate_pubkey_match(mint_b.key(), &pool_state.token_b, AmmError::InvalidMint)?;
+        validate_mint_token_program(token_program_a, &pool_state.token_program_a)?;
+        validate_mint_token_program(token_program_b, &pool_state.token_program_b)?;
 
         let total_supply = lp_mint_acc.supply();
         if total_supply == 0 {
-            return Err(ProgramError::InvalidAccountData);
+            return Err(AmmError::EmptyPool.into());
         }
 
         if user_lp_acc.amount() < data.amount_in {
-            return Err(ProgramError::InsufficientFunds);
+            return Err(AmmError::InsufficientLpBalance.into());
         }
 
-        let amount_a_out = data
-            .amount_in
-            .checked_mul(pool_state.reserve_a)
-            .ok_or(ProgramError::ArithmeticOverflow)?
-            .checked_div(total_supply)
+        let remaining_supply = total_supply
+            .checked_sub(data.amount_in)
             .ok_or(ProgramError::ArithmeticOverflow)?;
+        if remaining_supply < MINIMUM_LIQUIDITY {
+            return Err(AmmError::InsufficientLiquidity.into());
+        }
 
-        let amount_b_out = data
-            .amount_in
-            .checked_mul(pool_state.reserve_b)
-            .ok_or(ProgramError::ArithmeticOverflow)?
-            .checked_div(total_supply)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let (amount_a_out, amount_b_out) =
+            pool_state.curve(Clock::get()?.unix_timestamp)?.withdraw(
+                data.amount_in,
+                pool_state.reserve_a,
+                pool_state.reserve_b,
+                total_supply,
+            )?;
 
         if amount_a_out == 0 || amount_b_out == 0 {
-            return Err(ProgramError::InvalidAccountData);
+            return Err(AmmError::ZeroOutput.into());
         }
 
         if amount_a_out < data.min_amount_a {
-            return Err(ProgramError::InsufficientFunds);
+            return Err(AmmError::SlippageExceeded.into());
         }
         if amount_b_out < data.min_amount_b {
-            return Err(ProgramError::InsufficientFunds);
+            return Err(AmmError::SlippageExceeded.into());
         }
 
         if pool_state.reserve_a < amount_a_out {
-            return Err(ProgramError::InsufficientFunds);
+            return Err(AmmError::InsufficientLiquidity.into());
         }
         if pool_state.reserve_b < amount_b_out {
-            return Err(ProgramError::InsufficientFunds);
+            return Err(AmmError::InsufficientLiquidity.into());
         }
         (amount_a_out, amount_b_out)
     };
@@ -128,29 +191,31 @@ pub fn process_withdraw(
     }
     .invoke()?;
 
-    let (pool_bump, token_a, token_b) = load_pool_data(pool)?;
-    let binding = [pool_bump];
-    let pool_seed = create_pool_seed(&binding, &token_a, &token_b);
-
-    Transfer {
-        from: vault_a,
-        to: user_token_a,
-        authority: pool,
-        amount: amount_a_out,
-    }
-    .invoke_signed(&[create_pool_signer(&pool_seed)])?;
+    transfer_from_pool(
+        pool,
+        vault_a,
+        mint_a,
+        user_token_a,
+        token_program_a,
+        amount_a_out,
+        hook_accounts,
+    )?;
 
-    Transfer {
-        from: vault_b,
-        to: user_token_b,
-        authority: pool,
-        amount: amount_b_out,
-    }
-    .invoke_signed(&[create_pool_signer(&pool_seed)])?;
+    transfer_from_pool(
+        pool,
+        vault_b,
+        mint_b,
+        user_token_b,
+        token_program_b,
+        amount_b_out,
+        hook_accounts,
+    )?;
 
     let mut pool_data = pool.try_borrow_mut_data()?;
     let pool_state = Pool::load_mut(&mut pool_data)?;
 
+    pool_state.update_oracle(Clock::get()?.unix_timestamp)?;
+
     pool_state.reserve_a = pool_state
         .reserve_a
         .checked_sub(amount_a_out)
@@ -0,0 +1,211 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{Sysvar, clock::Clock},
+};
+use pinocchio_token::{
+    instructions::Burn,
+    state::{Mint, TokenAccount},
+};
+
+use super::{
+    deadline::{Deadline, validate_deadline},
+    token::load_token_account,
+    utils::transfer_from_pool,
+    validators::{
+        validate_non_zero, validate_pool_side, validate_pool_status, validate_pubkey_match,
+        validate_signer, validate_token_program,
+    },
+};
+use crate::{
+    constants::{MINIMUM_LIQUIDITY, SWAPS_DISABLED, WITHDRAWALS_DISABLED},
+    curve::SwapCurve,
+    error::AmmError,
+    states::Pool,
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct WithdrawSingleInstructionData {
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+}
+
+impl WithdrawSingleInstructionData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+}
+
+/// Burns LP tokens and pays their share of the pool out in one token.
+///
+/// The share of the other token is swapped into the output token against the
+/// reserves left after the withdrawal, paying the swap fee, and never leaves
+/// the pool.
+pub fn process_withdraw_single(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instructions: &[u8],
+) -> ProgramResult {
+    let [
+        user,
+        pool,
+        lp_mint,
+        output_vault,
+        user_lp_token,
+        user_output_token,
+        token_program,
+        output_mint,
+        output_token_program,
+        hook_accounts @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    validate_signer(user)?;
+    validate_token_program(token_program)?;
+
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let (instructions, deadline) =
+        Deadline::split(instructions, WithdrawSingleInstructionData::LEN)?;
+    validate_deadline(deadline)?;
+
+    let data: WithdrawSingleInstructionData = bytemuck::checked::pod_read_unaligned(instructions);
+
+    validate_non_zero(data.amount_in)?;
+
+    let amount_out = {
+        let mut pool_data = pool.try_borrow_mut_data()?;
+        let pool_state = Pool::load_mut(&mut pool_data)?;
+
+        let lp_mint_acc = Mint::from_account_info(lp_mint)?;
+        let user_lp_acc = TokenAccount::from_account_info(user_lp_token)?;
+        let user_output_acc = load_token_account(user_output_token)?;
+
+        // The other side's share is swapped, so it needs swaps enabled as well.
+        validate_pool_status(
+            pool_state,
+            WITHDRAWALS_DISABLED,
+            AmmError::WithdrawalsDisabled,
+        )?;
+        validate_pool_status(pool_state, SWAPS_DISABLED, AmmError::SwapsDisabled)?;
+        validate_pubkey_match(lp_mint.key(), &pool_state.lp_mint, AmmError::InvalidLpMint)?;
+        let is_a = validate_pool_side(
+            program_id,
+            pool.key(),
+            pool_state,
+            output_vault,
+            output_mint,
+            output_token_program,
+        )?;
+        validate_pubkey_match(
+            user_lp_acc.mint(),
+            &pool_state.lp_mint,
+            AmmError::InvalidLpMint,
+        )?;
+        validate_pubkey_match(
+            user_output_acc.mint(),
+            output_mint.key(),
+            AmmError::InvalidMint,
+        )?;
+        validate_pubkey_match(
+            user_lp_acc.owner(),
+            user.key(),
+            AmmError::InvalidTokenAccountOwner,
+        )?;
+        validate_pubkey_match(
+            user_output_acc.owner(),
+            user.key(),
+            AmmError::InvalidTokenAccountOwner,
+        )?;
+
+        let total_supply = lp_mint_acc.supply();
+        if total_supply == 0 {
+            return Err(AmmError::EmptyPool.into());
+        }
+
+        if user_lp_acc.amount() < data.amount_in {
+            return Err(AmmError::InsufficientLpBalance.into());
+        }
+
+        let remaining_supply = total_supply
+            .checked_sub(data.amount_in)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if remaining_supply < MINIMUM_LIQUIDITY {
+            return Err(AmmError::InsufficientLiquidity.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        pool_state.update_oracle(now)?;
+
+        let curve = pool_state.curve(now)?;
+        let (reserve_out, reserve_in) = if is_a {
+            (pool_state.reserve_a, pool_state.reserve_b)
+        } else {
+            (pool_state.reserve_b, pool_state.reserve_a)
+        };
+
+        // The share of each side, as `Withdraw` would pay it.
+        let (share_a, share_b) = curve.withdraw(
+            data.amount_in,
+            pool_state.reserve_a,
+            pool_state.reserve_b,
+            total_supply,
+        )?;
+        let (share_out, share_in) = if is_a {
+            (share_a, share_b)
+        } else {
+            (share_b, share_a)
+        };
+
+        // Swap the other side's share against what is left in the pool.
+        let reserve_out = reserve_out - share_out;
+        let reserve_in = reserve_in - share_in;
+        let (swap_out, fee) =
+            curve.swap_out(share_in, reserve_in, reserve_out, pool_state.fee_rate)?;
+
+        let (output_reserve, input_reserve) = if is_a {
+            (&mut pool_state.reserve_a, &mut pool_state.reserve_b)
+        } else {
+            (&mut pool_state.reserve_b, &mut pool_state.reserve_a)
+        };
+        *output_reserve = reserve_out;
+        *input_reserve = reserve_in;
+        pool_state.apply_swap(!is_a, share_in, swap_out, fee)?;
+
+        share_out
+            .checked_add(swap_out)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+    };
+
+    if amount_out == 0 {
+        return Err(AmmError::ZeroOutput.into());
+    }
+
+    if amount_out < data.min_amount_out {
+        return Err(AmmError::SlippageExceeded.into());
+    }
+
+    Burn {
+        mint: lp_mint,
+        account: user_lp_token,
+        authority: user,
+        amount: data.amount_in,
+    }
+    .invoke()?;
+
+    transfer_from_pool(
+        pool,
+        output_vault,
+        output_mint,
+        user_output_token,
+        output_token_program,
+        amount_out,
+        hook_accounts,
+    )
+}
@@ -1,6 +1,11 @@
 mod entrypoint;
 
 pub mod constants;
+pub mod curve;
+pub mod error;
 pub mod helper;
 pub mod instructions;
+pub mod math;
 pub mod states;
+#[cfg(not(target_os = "solana"))]
+pub mod transfer_hook;
@@ -0,0 +1,137 @@
+//! Checked `u64` arithmetic with `u128` intermediates.
+//!
+//! Products of two `u64` values always fit in a `u128`, so formulas only fail
+//! when the final result does not fit in a `u64`. `mul_div_wide` extends this
+//! to `u128` values with a 256-bit product. Every division names its
+//! rounding direction; callers pick the one that favours the pool.
+
+use pinocchio::program_error::ProgramError;
+
+/// `a * b / denominator`, rounded down.
+pub fn mul_div_floor(a: u64, b: u64, denominator: u64) -> Result<u64, ProgramError> {
+    if denominator == 0 {
+        return Err(ProgramError::ArithmeticOverflow);
+    }
+    to_u64(a as u128 * b as u128 / denominator as u128)
+}
+
+/// `a * b / denominator`, rounded up.
+pub fn mul_div_ceil(a: u64, b: u64, denominator: u64) -> Result<u64, ProgramError> {
+    if denominator == 0 {
+        return Err(ProgramError::ArithmeticOverflow);
+    }
+    to_u64((a as u128 * b as u128).div_ceil(denominator as u128))
+}
+
+/// `a * b / denominator` on `u128` values, rounded down, with a 256-bit
+/// intermediate product.
+pub fn mul_div_wide(a: u128, b: u128, denominator: u128) -> Result<u128, ProgramError> {
+    mul_add_div_wide(a, b, 0, denominator)
+}
+
+/// `(a * b + addend) / denominator` on `u128` values, rounded down, with a
+/// 256-bit intermediate.
+pub fn mul_add_div_wide(
+    a: u128,
+    b: u128,
+    addend: u128,
+    denominator: u128,
+) -> Result<u128, ProgramError> {
+    if denominator == 0 {
+        return Err(ProgramError::ArithmeticOverflow);
+    }
+
+    let (high, low) = widening_mul(a, b);
+    let (low, carry) = low.overflowing_add(addend);
+    let high = high
+        .checked_add(carry as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if high == 0 {
+        return Ok(low / denominator);
+    }
+    // The quotient needs more than 128 bits.
+    if high >= denominator {
+        return Err(ProgramError::ArithmeticOverflow);
+    }
+
+    Ok(div_wide(high, low, denominator))
+}
+
+/// The 256-bit product of `a` and `b`, as `(high, low)` halves.
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+
+    let low = a0 * b0;
+    let (middle, middle_carry) = (a1 * b0).overflowing_add(a0 * b1);
+    let (low, low_carry) = low.overflowing_add(middle << 64);
+    let high = a1 * b1 + (middle >> 64) + ((middle_carry as u128) << 64) + low_carry as u128;
+
+    (high, low)
+}
+
+/// `(high * 2^128 + low) / denominator` for `high < denominator`, which keeps
+/// the quotient within 128 bits.
+///
+/// Long division in 64-bit digits after normalizing the denominator (Hacker's
+/// Delight, `divlu`), so every step is a native `u128` operation.
+fn div_wide(high: u128, low: u128, denominator: u128) -> u128 {
+    const MASK: u128 = u64::MAX as u128;
+
+    let shift = denominator.leading_zeros();
+    let denominator = denominator << shift;
+    let (high, low) = if shift == 0 {
+        (high, low)
+    } else {
+        ((high << shift) | (low >> (128 - shift)), low << shift)
+    };
+
+    let (d1, d0) = (denominator >> 64, denominator & MASK);
+    let (low1, low0) = (low >> 64, low & MASK);
+
+    let quotient_digit = |numerator: u128, next_digit: u128| {
+        let mut q = numerator / d1;
+        let mut r = numerator % d1;
+        while q > MASK || q * d0 > ((r << 64) | next_digit) {
+            q -= 1;
+            r += d1;
+            if r > MASK {
+                break;
+            }
+        }
+        q
+    };
+
+    let q1 = quotient_digit(high, low1);
+    let remainder = ((high << 64) | low1).wrapping_sub(q1.wrapping_mul(denominator));
+    let q0 = quotient_digit(remainder, low0);
+
+    (q1 << 64) | q0
+}
+
+/// `floor(sqrt(n))`, exact for every `u128`.
+///
+/// Newton's method from the smallest power of two above the root. The first
+/// guess is within a factor of two, so it converges in at most seven steps,
+/// decreasing monotonically until it reaches the floor of the root.
+pub fn integer_sqrt(n: u128) -> u128 {
+    if n == 0 {
+        return 0;
+    }
+
+    let bits = u128::BITS - n.leading_zeros();
+    let mut x = 1_u128 << bits.div_ceil(2);
+    loop {
+        let y = (x + n / x) >> 1;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+fn to_u64(value: u128) -> Result<u64, ProgramError> {
+    u64::try_from(value).map_err(|_| ProgramError::ArithmeticOverflow)
+}
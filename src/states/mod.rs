@@ -1,3 +1,7 @@
+pub mod observation;
 pub mod pool;
+pub mod protocol_config;
 
+pub use observation::Observation;
 pub use pool::Pool;
+pub use protocol_config::ProtocolConfig;
@@ -0,0 +1,50 @@
+use bytemuck::{Pod, Zeroable};
+
+/// Snapshot of a pool's price accumulators, returned by `Observe`.
+///
+/// Cumulative prices are Q64.64 fixed point values summed per second and
+/// stored as little-endian `u128` bytes. They wrap on overflow, so always
+/// difference two observations with wrapping arithmetic.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct Observation {
+    pub timestamp: i64,
+    /// Cumulative price of token A in token B.
+    pub price_a_cumulative: [u8; 16],
+    /// Cumulative price of token B in token A.
+    pub price_b_cumulative: [u8; 16],
+}
+
+impl Observation {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+
+    pub fn price_a_cumulative(&self) -> u128 {
+        u128::from_le_bytes(self.price_a_cumulative)
+    }
+
+    pub fn price_b_cumulative(&self) -> u128 {
+        u128::from_le_bytes(self.price_b_cumulative)
+    }
+
+    /// Time-weighted average prices of token A and token B, in Q64.64,
+    /// between `earlier` and `self`.
+    ///
+    /// Returns `None` when no time has elapsed between the two observations.
+    pub fn twap_since(&self, earlier: &Observation) -> Option<(u128, u128)> {
+        let elapsed = self.timestamp.checked_sub(earlier.timestamp)?;
+        if elapsed <= 0 {
+            return None;
+        }
+
+        let price_a = self
+            .price_a_cumulative()
+            .wrapping_sub(earlier.price_a_cumulative())
+            / elapsed as u128;
+        let price_b = self
+            .price_b_cumulative()
+            .wrapping_sub(earlier.price_b_cumulative())
+            / elapsed as u128;
+
+        Some((price_a, price_b))
+    }
+}
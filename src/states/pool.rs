@@ -1,5 +1,11 @@
 use bytemuck::{Pod, Zeroable};
-use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use pinocchio::{ProgramResult, program_error::ProgramError, pubkey::Pubkey};
+
+use super::Observation;
+use crate::{
+    curve::{Curve, SwapCurve},
+    math::mul_div_floor,
+};
 
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
@@ -13,9 +19,57 @@ pub struct Pool {
     pub reserve_a: u64,
     pub reserve_b: u64,
     pub fee_rate: u16,
+    /// Share of every swap fee, in basis points of the fee, that accrues to the
+    /// protocol instead of the LPs.
+    pub protocol_fee_rate: u16,
     pub bump: u8,
     pub lp_mint_bump: u8,
-    pub _padding: [u8; 4],
+    /// Fee on flash loans, in basis points of the amount borrowed. Paid to the LPs.
+    pub flash_loan_fee_rate: u16,
+    /// Protocol fees held in `vault_a` but excluded from `reserve_a`.
+    pub protocol_fees_a: u64,
+    /// Protocol fees held in `vault_b` but excluded from `reserve_b`.
+    pub protocol_fees_b: u64,
+    /// Unix timestamp of the last oracle update.
+    pub last_update_timestamp: i64,
+    /// Q64.64 price of token A in token B summed per second, as `u128` bytes.
+    pub price_a_cumulative: [u8; 16],
+    /// Q64.64 price of token B in token A summed per second, as `u128` bytes.
+    pub price_b_cumulative: [u8; 16],
+    /// Token A lent out by a flash loan that has not been repaid yet.
+    pub flash_loan_a: u64,
+    /// Token B lent out by a flash loan that has not been repaid yet.
+    pub flash_loan_b: u64,
+    /// Token program (SPL Token or Token-2022) that owns `token_a`.
+    pub token_program_a: Pubkey,
+    /// Token program (SPL Token or Token-2022) that owns `token_b`.
+    pub token_program_b: Pubkey,
+    /// Authority proposed by `ProposeAuthority`, which takes over once it calls
+    /// `AcceptAuthority`. All zeroes when no transfer is pending.
+    pub pending_authority: Pubkey,
+    /// Amplification coefficient of a StableSwap pool at `ramp_start_timestamp`.
+    /// Zero on a constant-product pool.
+    pub initial_amp: u64,
+    /// Amplification coefficient of a StableSwap pool from
+    /// `ramp_stop_timestamp` on. Zero on a constant-product pool.
+    pub target_amp: u64,
+    /// Unix timestamp at which the current amplification ramp started.
+    pub ramp_start_timestamp: i64,
+    /// Unix timestamp at which the current amplification ramp ends.
+    pub ramp_stop_timestamp: i64,
+    /// Bump of the `vault_a` PDA, derived from the pool and `token_a`.
+    pub vault_a_bump: u8,
+    /// Bump of the `vault_b` PDA, derived from the pool and `token_b`.
+    pub vault_b_bump: u8,
+    /// Bump of the LP vault PDA, derived from the pool and `lp_mint`, which
+    /// holds the locked minimum liquidity.
+    pub lp_vault_bump: u8,
+    /// Operations disabled by the authority, as `*_DISABLED` bits from
+    /// `constants`. Zero when the pool is fully open.
+    pub status: u8,
+    /// Invariant the pool trades on, as a `CURVE_*` value from `curve`.
+    pub curve_type: u8,
+    pub _padding: [u8; 3],
 }
 
 impl Pool {
@@ -31,8 +85,57 @@ impl Pool {
         self.reserve_a = args.reserve_a;
         self.reserve_b = args.reserve_b;
         self.fee_rate = args.fee_rate;
+        self.protocol_fee_rate = args.protocol_fee_rate;
         self.bump = args.bump;
         self.lp_mint_bump = args.lp_mint_bump;
+        self.flash_loan_fee_rate = args.flash_loan_fee_rate;
+        self.protocol_fees_a = args.protocol_fees_a;
+        self.protocol_fees_b = args.protocol_fees_b;
+        self.last_update_timestamp = args.last_update_timestamp;
+        self.price_a_cumulative = args.price_a_cumulative;
+        self.price_b_cumulative = args.price_b_cumulative;
+        self.flash_loan_a = args.flash_loan_a;
+        self.flash_loan_b = args.flash_loan_b;
+        self.token_program_a = args.token_program_a;
+        self.token_program_b = args.token_program_b;
+        self.pending_authority = args.pending_authority;
+        self.initial_amp = args.initial_amp;
+        self.target_amp = args.target_amp;
+        self.ramp_start_timestamp = args.ramp_start_timestamp;
+        self.ramp_stop_timestamp = args.ramp_stop_timestamp;
+        self.vault_a_bump = args.vault_a_bump;
+        self.vault_b_bump = args.vault_b_bump;
+        self.lp_vault_bump = args.lp_vault_bump;
+        self.status = args.status;
+        self.curve_type = args.curve_type;
+    }
+
+    /// The curve selected by `curve_type`, with the amplification coefficient
+    /// at `now`.
+    pub fn curve(&self, now: i64) -> Result<Curve, ProgramError> {
+        Curve::new(self.curve_type, self.amp(now))
+    }
+
+    /// Amplification coefficient at `now`, moving linearly from `initial_amp`
+    /// to `target_amp` between the ramp timestamps.
+    pub fn amp(&self, now: i64) -> u64 {
+        if now >= self.ramp_stop_timestamp {
+            return self.target_amp;
+        }
+        if now <= self.ramp_start_timestamp {
+            return self.initial_amp;
+        }
+
+        let elapsed = now.abs_diff(self.ramp_start_timestamp) as u128;
+        let duration = self.ramp_stop_timestamp.abs_diff(self.ramp_start_timestamp) as u128;
+        let change =
+            (self.target_amp.abs_diff(self.initial_amp) as u128 * elapsed / duration) as u64;
+
+        if self.target_amp > self.initial_amp {
+            self.initial_amp + change
+        } else {
+            self.initial_amp - change
+        }
     }
 
     pub fn load_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
@@ -42,4 +145,89 @@ impl Pool {
     pub fn load(data: &[u8]) -> Result<&Self, ProgramError> {
         bytemuck::try_from_bytes(data).map_err(|_| ProgramError::InvalidAccountData)
     }
+
+    /// Accumulates the prices of the current reserves over the time elapsed
+    /// since the last update.
+    ///
+    /// The prices are the curve's marginal prices, which for StableSwap differ
+    /// from the reserve ratio.
+    ///
+    /// Must run before the reserves change, so each period is weighted by the
+    /// price that actually held during it.
+    pub fn update_oracle(&mut self, now: i64) -> ProgramResult {
+        let elapsed = now.saturating_sub(self.last_update_timestamp);
+        if elapsed <= 0 {
+            return Ok(());
+        }
+
+        if self.reserve_a != 0 && self.reserve_b != 0 {
+            let curve = self.curve(now)?;
+            let price_a = curve.spot_price(self.reserve_a, self.reserve_b)?;
+            let price_b = curve.spot_price(self.reserve_b, self.reserve_a)?;
+
+            self.price_a_cumulative = u128::from_le_bytes(self.price_a_cumulative)
+                .wrapping_add(price_a.wrapping_mul(elapsed as u128))
+                .to_le_bytes();
+            self.price_b_cumulative = u128::from_le_bytes(self.price_b_cumulative)
+                .wrapping_add(price_b.wrapping_mul(elapsed as u128))
+                .to_le_bytes();
+        }
+
+        self.last_update_timestamp = now;
+
+        Ok(())
+    }
+
+    /// Oracle accumulators as they would read at `now`, without modifying the pool.
+    pub fn observe(&self, now: i64) -> Result<Observation, ProgramError> {
+        let mut pool = *self;
+        pool.update_oracle(now)?;
+
+        Ok(Observation {
+            timestamp: pool.last_update_timestamp,
+            price_a_cumulative: pool.price_a_cumulative,
+            price_b_cumulative: pool.price_b_cumulative,
+        })
+    }
+
+    /// Records a swap of `amount_in` for `amount_out` in the reserves.
+    ///
+    /// The protocol's share of `fee` is moved out of the input reserve into the
+    /// protocol fee counter for that side.
+    pub fn apply_swap(
+        &mut self,
+        is_a_to_b: bool,
+        amount_in: u64,
+        amount_out: u64,
+        fee: u64,
+    ) -> ProgramResult {
+        // Rounded down, so the remainder of the fee stays with the LPs.
+        let protocol_fee = mul_div_floor(fee, self.protocol_fee_rate as u64, 10000)?;
+
+        let (reserve_in, reserve_out, protocol_fees_in) = if is_a_to_b {
+            (
+                &mut self.reserve_a,
+                &mut self.reserve_b,
+                &mut self.protocol_fees_a,
+            )
+        } else {
+            (
+                &mut self.reserve_b,
+                &mut self.reserve_a,
+                &mut self.protocol_fees_b,
+            )
+        };
+
+        *reserve_in = reserve_in
+            .checked_add(amount_in - protocol_fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        *reserve_out = reserve_out
+            .checked_sub(amount_out)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        *protocol_fees_in = protocol_fees_in
+            .checked_add(protocol_fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,30 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+/// Protocol-wide settings, held in the `CONFIG_SEED` PDA. The program's upgrade
+/// authority creates it once with `InitializeConfig`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ProtocolConfig {
+    /// Key that runs the protocol treasury. Only it can call
+    /// `CollectProtocolFees`, and protocol fees are only paid into token
+    /// accounts it owns.
+    pub authority: Pubkey,
+    /// Protocol share of swap fees, in basis points of the fee, given to every
+    /// new pool. The protocol authority can change it per pool later.
+    pub protocol_fee_rate: u16,
+    pub bump: u8,
+    pub _padding: [u8; 1],
+}
+
+impl ProtocolConfig {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+
+    pub fn load_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        bytemuck::try_from_bytes_mut(data).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    pub fn load(data: &[u8]) -> Result<&Self, ProgramError> {
+        bytemuck::try_from_bytes(data).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
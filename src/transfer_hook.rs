@@ -0,0 +1,284 @@
+//! Off-chain resolution of the accounts a Token-2022 transfer hook needs.
+//!
+//! A transfer of a mint with a `TransferHook` extension only succeeds if the
+//! token program is given the hook program, its validation account and every
+//! extra account listed in that validation account. The pool instructions
+//! forward their remaining accounts to each transfer, so clients append the
+//! accounts returned by [`resolve_transfer_hook_accounts`] for every hook mint
+//! the instruction moves.
+
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+/// Token-2022 `TransferHook` mint extension type.
+const TRANSFER_HOOK_EXTENSION: u16 = 14;
+
+/// Offset of the account type byte that precedes Token-2022 extensions.
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+
+/// Seed of the validation account, derived from the mint under the hook program.
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// `Execute` instruction discriminator of the transfer hook interface, which
+/// also tags the extra account list in the validation account.
+pub const EXECUTE_DISCRIMINATOR: [u8; 8] = [105, 37, 101, 197, 75, 251, 102, 26];
+
+/// Size of one packed `ExtraAccountMeta`.
+const EXTRA_ACCOUNT_META_LEN: usize = 35;
+
+/// Position of the validation account in the `Execute` instruction. Extra
+/// accounts follow it, after source, mint, destination and authority.
+const VALIDATION_ACCOUNT_INDEX: usize = 4;
+
+/// An account to append to a pool instruction for a transfer hook.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HookAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl HookAccountMeta {
+    fn readonly(pubkey: Pubkey) -> Self {
+        Self {
+            pubkey,
+            is_signer: false,
+            is_writable: false,
+        }
+    }
+}
+
+/// Returns the transfer hook program of a Token-2022 mint, if it has one.
+pub fn transfer_hook_program_id(mint_data: &[u8]) -> Option<Pubkey> {
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= mint_data.len() {
+        let extension = u16::from_le_bytes([mint_data[offset], mint_data[offset + 1]]);
+        let length = u16::from_le_bytes([mint_data[offset + 2], mint_data[offset + 3]]) as usize;
+        let value = mint_data.get(offset + 4..offset + 4 + length)?;
+
+        if extension == TRANSFER_HOOK_EXTENSION {
+            // [authority (32), program_id (32)], zero meaning none.
+            let program_id: Pubkey = value.get(32..64)?.try_into().ok()?;
+            return (program_id != Pubkey::default()).then_some(program_id);
+        }
+
+        offset += 4 + length;
+    }
+    None
+}
+
+/// Address of the validation account listing the extra accounts of `mint`.
+pub fn extra_account_metas_address(mint: &Pubkey, hook_program: &Pubkey) -> Pubkey {
+    find_program_address(&[EXTRA_ACCOUNT_METAS_SEED, mint], hook_program)
+}
+
+/// Resolves the accounts a transfer of `amount` from `source` to
+/// `destination` needs for the hook of `mint`.
+///
+/// Returns the extra accounts in list order, followed by the hook program and
+/// the validation account, or nothing if the mint has no transfer hook.
+/// `get_account_data` is asked for the validation account and for any account
+/// whose data seeds an extra account address.
+///
+/// When an instruction moves two hook mints, or the same mint twice, the
+/// results can be concatenated; duplicates may be dropped.
+pub fn resolve_transfer_hook_accounts(
+    mint: &Pubkey,
+    mint_data: &[u8],
+    source: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+    mut get_account_data: impl FnMut(&Pubkey) -> Option<Vec<u8>>,
+) -> Result<Vec<HookAccountMeta>, ProgramError> {
+    let Some(hook_program) = transfer_hook_program_id(mint_data) else {
+        return Ok(Vec::new());
+    };
+
+    let validation_account = extra_account_metas_address(mint, &hook_program);
+    let validation_data =
+        get_account_data(&validation_account).ok_or(ProgramError::UninitializedAccount)?;
+    let extra_metas = extra_account_metas(&validation_data)?;
+
+    let mut instruction_data = EXECUTE_DISCRIMINATOR.to_vec();
+    instruction_data.extend_from_slice(&amount.to_le_bytes());
+
+    // Account list of the `Execute` instruction the seeds index into.
+    let mut accounts = vec![
+        HookAccountMeta::readonly(*source),
+        HookAccountMeta::readonly(*mint),
+        HookAccountMeta::readonly(*destination),
+        HookAccountMeta::readonly(*authority),
+        HookAccountMeta::readonly(validation_account),
+    ];
+
+    for meta in extra_metas.chunks_exact(EXTRA_ACCOUNT_META_LEN) {
+        let discriminator = meta[0];
+        let config: &[u8; 32] = meta[1..33].try_into().unwrap();
+
+        let pubkey = match discriminator {
+            0 => *config,
+            1 => resolve_pda(
+                config,
+                &hook_program,
+                &instruction_data,
+                &accounts,
+                &mut get_account_data,
+            )?,
+            2 => resolve_pubkey_data(config, &instruction_data, &accounts, &mut get_account_data)?,
+            128.. => {
+                let program = account_at(&accounts, (discriminator - 128) as usize)?;
+                resolve_pda(
+                    config,
+                    &program,
+                    &instruction_data,
+                    &accounts,
+                    &mut get_account_data,
+                )?
+            }
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        accounts.push(HookAccountMeta {
+            pubkey,
+            is_signer: meta[33] != 0,
+            is_writable: meta[34] != 0,
+        });
+    }
+
+    let mut hook_accounts = accounts.split_off(VALIDATION_ACCOUNT_INDEX + 1);
+    hook_accounts.push(HookAccountMeta::readonly(hook_program));
+    hook_accounts.push(HookAccountMeta::readonly(validation_account));
+    Ok(hook_accounts)
+}
+
+/// Finds the packed `ExtraAccountMeta` entries stored under
+/// [`EXECUTE_DISCRIMINATOR`] in the validation account TLV data.
+fn extra_account_metas(data: &[u8]) -> Result<&[u8], ProgramError> {
+    let mut offset = 0;
+    while offset + 12 <= data.len() {
+        let discriminator = &data[offset..offset + 8];
+        let length = u32::from_le_bytes(data[offset + 8..offset + 12].try_into().unwrap()) as usize;
+        let value = data
+            .get(offset + 12..offset + 12 + length)
+            .ok_or(ProgramError::InvalidAccountData)?;
+
+        if discriminator == EXECUTE_DISCRIMINATOR {
+            // [count (u32), entries]
+            let count = u32::from_le_bytes(
+                value
+                    .get(..4)
+                    .ok_or(ProgramError::InvalidAccountData)?
+                    .try_into()
+                    .unwrap(),
+            ) as usize;
+            return value
+                .get(4..4 + count * EXTRA_ACCOUNT_META_LEN)
+                .ok_or(ProgramError::InvalidAccountData);
+        }
+
+        offset += 12 + length;
+    }
+    Err(ProgramError::InvalidAccountData)
+}
+
+/// Derives a PDA of `program` from the packed seed configuration.
+fn resolve_pda(
+    config: &[u8; 32],
+    program: &Pubkey,
+    instruction_data: &[u8],
+    accounts: &[HookAccountMeta],
+    get_account_data: &mut impl FnMut(&Pubkey) -> Option<Vec<u8>>,
+) -> Result<Pubkey, ProgramError> {
+    let mut seeds: Vec<Vec<u8>> = Vec::new();
+    let mut offset = 0;
+
+    while offset < config.len() {
+        let field = |index: usize| {
+            config
+                .get(offset + index)
+                .map(|&byte| byte as usize)
+                .ok_or(ProgramError::InvalidAccountData)
+        };
+
+        match config[offset] {
+            0 => break,
+            // Literal { length, bytes }
+            1 => {
+                let length = field(1)?;
+                let bytes = config
+                    .get(offset + 2..offset + 2 + length)
+                    .ok_or(ProgramError::InvalidAccountData)?;
+                seeds.push(bytes.to_vec());
+                offset += 2 + length;
+            }
+            // InstructionData { index, length }
+            2 => {
+                let (index, length) = (field(1)?, field(2)?);
+                let bytes = instruction_data
+                    .get(index..index + length)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                seeds.push(bytes.to_vec());
+                offset += 3;
+            }
+            // AccountKey { index }
+            3 => {
+                seeds.push(account_at(accounts, field(1)?)?.to_vec());
+                offset += 2;
+            }
+            // AccountData { account_index, data_index, length }
+            4 => {
+                let (account_index, data_index, length) = (field(1)?, field(2)?, field(3)?);
+                let data = get_account_data(&account_at(accounts, account_index)?)
+                    .ok_or(ProgramError::UninitializedAccount)?;
+                let bytes = data
+                    .get(data_index..data_index + length)
+                    .ok_or(ProgramError::AccountDataTooSmall)?;
+                seeds.push(bytes.to_vec());
+                offset += 4;
+            }
+            _ => return Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+    Ok(find_program_address(&seeds, program))
+}
+
+/// Reads an address out of the instruction data or another account's data.
+fn resolve_pubkey_data(
+    config: &[u8; 32],
+    instruction_data: &[u8],
+    accounts: &[HookAccountMeta],
+    get_account_data: &mut impl FnMut(&Pubkey) -> Option<Vec<u8>>,
+) -> Result<Pubkey, ProgramError> {
+    let read = |data: &[u8], index: usize| -> Result<Pubkey, ProgramError> {
+        data.get(index..index + 32)
+            .ok_or(ProgramError::AccountDataTooSmall)
+            .map(|bytes| bytes.try_into().unwrap())
+    };
+
+    match config[0] {
+        // InstructionData { index }
+        1 => read(instruction_data, config[1] as usize),
+        // AccountData { account_index, data_index }
+        2 => {
+            let data = get_account_data(&account_at(accounts, config[1] as usize)?)
+                .ok_or(ProgramError::UninitializedAccount)?;
+            read(&data, config[2] as usize)
+        }
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn account_at(accounts: &[HookAccountMeta], index: usize) -> Result<Pubkey, ProgramError> {
+    accounts
+        .get(index)
+        .map(|account| account.pubkey)
+        .ok_or(ProgramError::NotEnoughAccountKeys)
+}
+
+fn find_program_address(seeds: &[&[u8]], program: &Pubkey) -> Pubkey {
+    solana_pubkey::Pubkey::find_program_address(seeds, &solana_pubkey::Pubkey::from(*program))
+        .0
+        .to_bytes()
+}
@@ -0,0 +1,553 @@
+#![allow(dead_code)]
+
+use amm_pinocchio::{
+    constants::{CONFIG_SEED, LP_MINT_SEED, POOL_SEED, VAULT_SEED},
+    curve::{CURVE_CONSTANT_PRODUCT, CURVE_STABLE_SWAP},
+    error::AmmError,
+    states::{Pool, ProtocolConfig},
+};
+use bytemuck::Zeroable;
+use mollusk_svm::{Mollusk, program};
+use solana_sdk::{
+    account::{Account, WritableAccount},
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+pub use mollusk_svm_programs_token::token2022::ID as TOKEN_2022_ID;
+
+pub fn amm_error(error: AmmError) -> ProgramError {
+    ProgramError::Custom(error as u32)
+}
+
+pub fn mint_account(mollusk: &Mollusk, mint_authority: Option<Pubkey>, supply: u64) -> Account {
+    let mut account = Account::new(
+        mollusk
+            .sysvars
+            .rent
+            .minimum_balance(spl_token::state::Mint::LEN),
+        spl_token::state::Mint::LEN,
+        &spl_token::ID,
+    );
+    Pack::pack(
+        spl_token::state::Mint {
+            mint_authority: mint_authority.into(),
+            supply,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        },
+        account.data_as_mut_slice(),
+    )
+    .unwrap();
+    account
+}
+
+pub fn token_account(mollusk: &Mollusk, mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    let mut account = Account::new(
+        mollusk
+            .sysvars
+            .rent
+            .minimum_balance(spl_token::state::Account::LEN),
+        spl_token::state::Account::LEN,
+        &spl_token::ID,
+    );
+    Pack::pack(
+        spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            delegate: COption::None,
+            state: spl_token::state::AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        },
+        account.data_as_mut_slice(),
+    )
+    .unwrap();
+    account
+}
+
+pub fn pool_account(mollusk: &Mollusk, program_id: &Pubkey, pool: &Pool) -> Account {
+    let mut account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(Pool::LEN),
+        Pool::LEN,
+        program_id,
+    );
+    account
+        .data_as_mut_slice()
+        .copy_from_slice(bytemuck::bytes_of(pool));
+    account
+}
+
+/// The protocol config PDA of `program_id`, holding `authority`.
+/// Protocol fee rate recorded in the protocol config of [`config_account`].
+pub const PROTOCOL_FEE_RATE: u16 = 1_000;
+
+pub fn config_account(
+    mollusk: &Mollusk,
+    program_id: &Pubkey,
+    authority: Pubkey,
+) -> (Pubkey, Account) {
+    let (config, bump) = Pubkey::find_program_address(&[CONFIG_SEED.as_bytes()], program_id);
+    let config_state = ProtocolConfig {
+        authority: authority.to_bytes(),
+        protocol_fee_rate: PROTOCOL_FEE_RATE,
+        bump,
+        _padding: [0; 1],
+    };
+
+    let mut account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(ProtocolConfig::LEN),
+        ProtocolConfig::LEN,
+        program_id,
+    );
+    account
+        .data_as_mut_slice()
+        .copy_from_slice(bytemuck::bytes_of(&config_state));
+    (config, account)
+}
+
+/// The vault PDA of `pool` for `mint`.
+pub fn vault_address(program_id: &Pubkey, pool: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool.as_ref(), mint.as_ref()],
+        program_id,
+    )
+}
+
+/// Moves a token account or mint to another token program, e.g. Token-2022.
+/// Both programs share the base account layouts.
+pub fn with_owner(mut account: Account, owner: Pubkey) -> Account {
+    account.owner = owner;
+    account
+}
+
+/// Token-2022 writes this account type byte after the base token account
+/// layout, followed by the extension TLV entries.
+fn with_extension(mut account: Account, account_type: u8, extension: u16, value: &[u8]) -> Account {
+    account.owner = TOKEN_2022_ID;
+    account.data.resize(spl_token::state::Account::LEN, 0);
+    account.data.push(account_type);
+    account.data.extend_from_slice(&extension.to_le_bytes());
+    account
+        .data
+        .extend_from_slice(&(value.len() as u16).to_le_bytes());
+    account.data.extend_from_slice(value);
+    account.lamports = 1_000_000_000;
+    account
+}
+
+/// A Token-2022 mint with a `TransferFeeConfig` charging `basis_points` on
+/// every transfer.
+pub fn transfer_fee_mint(mollusk: &Mollusk, supply: u64, basis_points: u16) -> Account {
+    let mut transfer_fee = Vec::new();
+    transfer_fee.extend_from_slice(&0u64.to_le_bytes()); // epoch
+    transfer_fee.extend_from_slice(&u64::MAX.to_le_bytes()); // maximum_fee
+    transfer_fee.extend_from_slice(&basis_points.to_le_bytes());
+
+    let mut config = vec![0; 64]; // no config or withdraw authority
+    config.extend_from_slice(&0u64.to_le_bytes()); // withheld_amount
+    config.extend_from_slice(&transfer_fee); // older_transfer_fee
+    config.extend_from_slice(&transfer_fee); // newer_transfer_fee
+
+    with_extension(mint_account(mollusk, None, supply), 1, 1, &config)
+}
+
+/// A Token-2022 token account with the `TransferFeeAmount` extension required
+/// to hold a transfer-fee mint.
+pub fn transfer_fee_token_account(
+    mollusk: &Mollusk,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) -> Account {
+    with_extension(
+        token_account(mollusk, mint, owner, amount),
+        2,
+        2,
+        &0u64.to_le_bytes(),
+    )
+}
+
+/// Replaces the account stored under `key`.
+pub fn set_account(accounts: &mut [(Pubkey, Account)], key: Pubkey, account: Account) {
+    let entry = accounts.iter_mut().find(|(k, _)| *k == key).unwrap();
+    entry.1 = account;
+}
+
+pub fn token_amount(account: &Account) -> u64 {
+    spl_token::state::Account::unpack(&account.data[..spl_token::state::Account::LEN])
+        .unwrap()
+        .amount
+}
+
+/// The accounts of one side of a `TestPool`.
+pub struct Side {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub user_account: Pubkey,
+    pub token_program: Pubkey,
+}
+
+/// A pool with funded vaults and a user holding both tokens and some LP.
+pub struct TestPool {
+    pub program_id: Pubkey,
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub lp_mint: Pubkey,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub lp_vault: Pubkey,
+    pub user_token_a: Pubkey,
+    pub user_token_b: Pubkey,
+    pub user_lp_token: Pubkey,
+    pub token_program_a: Pubkey,
+    pub token_program_b: Pubkey,
+    pub state: Pool,
+    pub lp_supply: u64,
+    pub user_balance: u64,
+    pub user_lp_balance: u64,
+}
+
+impl TestPool {
+    pub fn new(reserve_a: u64, reserve_b: u64, lp_supply: u64) -> (Mollusk, Self) {
+        Self::with_token_programs(
+            reserve_a,
+            reserve_b,
+            lp_supply,
+            spl_token::ID,
+            spl_token::ID,
+        )
+    }
+
+    /// A pool whose sides are held by the given token programs.
+    pub fn with_token_programs(
+        reserve_a: u64,
+        reserve_b: u64,
+        lp_supply: u64,
+        token_program_a: Pubkey,
+        token_program_b: Pubkey,
+    ) -> (Mollusk, Self) {
+        Self::with_curve(
+            reserve_a,
+            reserve_b,
+            lp_supply,
+            token_program_a,
+            token_program_b,
+            CURVE_CONSTANT_PRODUCT,
+            0,
+        )
+    }
+
+    /// A StableSwap pool with a constant amplification coefficient `amp`.
+    pub fn stable_swap(
+        reserve_a: u64,
+        reserve_b: u64,
+        lp_supply: u64,
+        amp: u64,
+    ) -> (Mollusk, Self) {
+        Self::with_curve(
+            reserve_a,
+            reserve_b,
+            lp_supply,
+            spl_token::ID,
+            spl_token::ID,
+            CURVE_STABLE_SWAP,
+            amp,
+        )
+    }
+
+    fn with_curve(
+        reserve_a: u64,
+        reserve_b: u64,
+        lp_supply: u64,
+        token_program_a: Pubkey,
+        token_program_b: Pubkey,
+        curve_type: u8,
+        amp: u64,
+    ) -> (Mollusk, Self) {
+        let program_id = Pubkey::new_unique();
+        let mut mollusk = Mollusk::new(&program_id, "tests/elfs/amm_pinocchio");
+        mollusk.add_program(&spl_token::ID, "tests/elfs/spl_token-3.5.0");
+        mollusk_svm_programs_token::token2022::add_program(&mut mollusk);
+
+        let token_a = Pubkey::new_from_array([0x03; 32]);
+        let token_b = Pubkey::new_from_array([0x02; 32]);
+
+        let (pool, bump) = Pubkey::find_program_address(
+            &[
+                POOL_SEED.as_bytes(),
+                token_a.as_ref(),
+                token_b.as_ref(),
+                &[curve_type],
+            ],
+            &program_id,
+        );
+        let (lp_mint, lp_mint_bump) =
+            Pubkey::find_program_address(&[LP_MINT_SEED.as_bytes(), pool.as_ref()], &program_id);
+
+        let (vault_a, vault_a_bump) = vault_address(&program_id, &pool, &token_a);
+        let (vault_b, vault_b_bump) = vault_address(&program_id, &pool, &token_b);
+        let (lp_vault, lp_vault_bump) = vault_address(&program_id, &pool, &lp_mint);
+
+        let state = Pool {
+            authority: pool.to_bytes(),
+            token_a: token_a.to_bytes(),
+            token_b: token_b.to_bytes(),
+            lp_mint: lp_mint.to_bytes(),
+            vault_a: vault_a.to_bytes(),
+            vault_b: vault_b.to_bytes(),
+            reserve_a,
+            reserve_b,
+            fee_rate: 30,
+            bump,
+            lp_mint_bump,
+            vault_a_bump,
+            vault_b_bump,
+            lp_vault_bump,
+            token_program_a: token_program_a.to_bytes(),
+            token_program_b: token_program_b.to_bytes(),
+            curve_type,
+            initial_amp: amp,
+            target_amp: amp,
+            ..Pool::zeroed()
+        };
+
+        let test_pool = Self {
+            program_id,
+            user: Pubkey::new_unique(),
+            pool,
+            lp_mint,
+            token_a,
+            token_b,
+            vault_a,
+            vault_b,
+            lp_vault,
+            user_token_a: Pubkey::new_from_array([0x07; 32]),
+            user_token_b: Pubkey::new_from_array([0x08; 32]),
+            user_lp_token: Pubkey::new_from_array([0x0A; 32]),
+            token_program_a,
+            token_program_b,
+            state,
+            lp_supply,
+            user_balance: 1_000_000,
+            user_lp_balance: lp_supply,
+        };
+
+        (mollusk, test_pool)
+    }
+
+    /// Every account the pool instructions may reference, keyed by address.
+    pub fn accounts(&self, mollusk: &Mollusk) -> Vec<(Pubkey, Account)> {
+        let (system_program, system_account) = program::keyed_account_for_system_program();
+        vec![
+            (self.user, Account::new(1_000_000_000, 0, &system_program)),
+            (
+                self.pool,
+                pool_account(mollusk, &self.program_id, &self.state),
+            ),
+            (
+                self.lp_mint,
+                mint_account(mollusk, Some(self.pool), self.lp_supply),
+            ),
+            (
+                self.token_a,
+                with_owner(
+                    mint_account(mollusk, None, 100_000_000),
+                    self.token_program_a,
+                ),
+            ),
+            (
+                self.token_b,
+                with_owner(
+                    mint_account(mollusk, None, 100_000_000),
+                    self.token_program_b,
+                ),
+            ),
+            (
+                self.vault_a,
+                with_owner(
+                    token_account(mollusk, self.token_a, self.pool, self.state.reserve_a),
+                    self.token_program_a,
+                ),
+            ),
+            (
+                self.vault_b,
+                with_owner(
+                    token_account(mollusk, self.token_b, self.pool, self.state.reserve_b),
+                    self.token_program_b,
+                ),
+            ),
+            (
+                self.user_token_a,
+                with_owner(
+                    token_account(mollusk, self.token_a, self.user, self.user_balance),
+                    self.token_program_a,
+                ),
+            ),
+            (
+                self.user_token_b,
+                with_owner(
+                    token_account(mollusk, self.token_b, self.user, self.user_balance),
+                    self.token_program_b,
+                ),
+            ),
+            (
+                self.user_lp_token,
+                token_account(mollusk, self.lp_mint, self.user, self.user_lp_balance),
+            ),
+            (
+                self.lp_vault,
+                token_account(mollusk, self.lp_mint, self.pool, 0),
+            ),
+            (system_program, system_account),
+            (
+                spl_token::ID,
+                program::create_program_account_loader_v3(&spl_token::ID),
+            ),
+            (
+                TOKEN_2022_ID,
+                program::create_program_account_loader_v3(&TOKEN_2022_ID),
+            ),
+        ]
+    }
+
+    pub fn swap_accounts(&self, a_to_b: bool) -> Vec<AccountMeta> {
+        let (input, output) = if a_to_b {
+            (self.side_a(), self.side_b())
+        } else {
+            (self.side_b(), self.side_a())
+        };
+        vec![
+            AccountMeta::new(self.user, true),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new_readonly(input.mint, false),
+            AccountMeta::new_readonly(output.mint, false),
+            AccountMeta::new(input.vault, false),
+            AccountMeta::new(output.vault, false),
+            AccountMeta::new(input.user_account, false),
+            AccountMeta::new(output.user_account, false),
+            AccountMeta::new_readonly(input.token_program, false),
+            AccountMeta::new_readonly(output.token_program, false),
+        ]
+    }
+
+    pub fn side_a(&self) -> Side {
+        Side {
+            mint: self.token_a,
+            vault: self.vault_a,
+            user_account: self.user_token_a,
+            token_program: self.token_program_a,
+        }
+    }
+
+    pub fn side_b(&self) -> Side {
+        Side {
+            mint: self.token_b,
+            vault: self.vault_b,
+            user_account: self.user_token_b,
+            token_program: self.token_program_b,
+        }
+    }
+
+    /// Mints and token programs appended to the liquidity instructions.
+    fn mint_accounts(&self) -> [AccountMeta; 4] {
+        [
+            AccountMeta::new_readonly(self.token_a, false),
+            AccountMeta::new_readonly(self.token_b, false),
+            AccountMeta::new_readonly(self.token_program_a, false),
+            AccountMeta::new_readonly(self.token_program_b, false),
+        ]
+    }
+
+    pub fn add_liquidity_accounts(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.user, true),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new(self.vault_a, false),
+            AccountMeta::new(self.vault_b, false),
+            AccountMeta::new(self.user_token_a, false),
+            AccountMeta::new(self.user_token_b, false),
+            AccountMeta::new(self.user_lp_token, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ]
+        .into_iter()
+        .chain(self.mint_accounts())
+        .chain([AccountMeta::new(self.lp_vault, false)])
+        .collect()
+    }
+
+    pub fn withdraw_accounts(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.user, true),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new(self.vault_a, false),
+            AccountMeta::new(self.vault_b, false),
+            AccountMeta::new(self.user_lp_token, false),
+            AccountMeta::new(self.user_token_a, false),
+            AccountMeta::new(self.user_token_b, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ]
+        .into_iter()
+        .chain(self.mint_accounts())
+        .collect()
+    }
+
+    pub fn deposit_single_accounts(&self, side: Side) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.user, true),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new(side.vault, false),
+            AccountMeta::new(side.user_account, false),
+            AccountMeta::new(self.user_lp_token, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(side.mint, false),
+            AccountMeta::new_readonly(side.token_program, false),
+        ]
+    }
+
+    pub fn withdraw_single_accounts(&self, side: Side) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.user, true),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new(side.vault, false),
+            AccountMeta::new(self.user_lp_token, false),
+            AccountMeta::new(side.user_account, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(side.mint, false),
+            AccountMeta::new_readonly(side.token_program, false),
+        ]
+    }
+
+    pub fn instruction(&self, data: Vec<u8>, accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }
+    }
+
+    pub fn pool_state(account: &Account) -> Pool {
+        bytemuck::pod_read_unaligned::<Pool>(&account.data)
+    }
+}
+
+/// Encodes a discriminator followed by little-endian `u64` arguments.
+pub fn ix_data(discriminator: u8, args: &[u64]) -> Vec<u8> {
+    let mut data = vec![discriminator];
+    for arg in args {
+        data.extend_from_slice(&arg.to_le_bytes());
+    }
+    data
+}
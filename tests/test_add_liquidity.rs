@@ -1,3 +1,4 @@
+use bytemuck::Zeroable;
 use {
     mollusk_svm::Mollusk,
     solana_sdk::{
@@ -9,9 +10,22 @@ use {
     },
 };
 
-use amm_pinocchio::constants::{LP_MINT_SEED, POOL_SEED};
+mod common;
+
+use amm_pinocchio::constants::{
+    DEPOSITS_DISABLED, LP_MINT_SEED, MINIMUM_LIQUIDITY, POOL_SEED, VAULT_SEED,
+};
+use amm_pinocchio::curve::{CURVE_CONSTANT_PRODUCT, StableSwap};
+use amm_pinocchio::error::AmmError;
+use amm_pinocchio::helper::calculate_deposit_amounts;
+use amm_pinocchio::instructions::add_liquidity::DepositAmounts;
+use amm_pinocchio::instructions::deadline::DEADLINE_UNIX_TIMESTAMP;
 use amm_pinocchio::states::Pool;
-use mollusk_svm::program;
+use common::{
+    TOKEN_2022_ID, TestPool, amm_error, ix_data, set_account, token_account, token_amount,
+    transfer_fee_mint, transfer_fee_token_account,
+};
+use mollusk_svm::{program, result::Check};
 use spl_token::state::Mint;
 
 #[test]
@@ -21,7 +35,7 @@ fn test_add_liquidity_success() {
 
     mollusk.add_program(&spl_token::ID, "tests/elfs/spl_token-3.5.0");
 
-    let (token_program, token_account) = (
+    let (token_program, token_program_account) = (
         spl_token::ID,
         program::create_program_account_loader_v3(&spl_token::ID),
     );
@@ -71,14 +85,22 @@ fn test_add_liquidity_success() {
     .unwrap();
 
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
-        &[POOL_SEED.as_bytes(), token_a.as_ref(), token_b.as_ref()],
+        &[
+            POOL_SEED.as_bytes(),
+            token_a.as_ref(),
+            token_b.as_ref(),
+            &[CURVE_CONSTANT_PRODUCT],
+        ],
         &program_id,
     );
 
     let (lp_mint, lp_mint_bump) =
         Pubkey::find_program_address(&[LP_MINT_SEED.as_bytes(), pool_pda.as_ref()], &program_id);
 
-    let vault_a = Pubkey::new_from_array([0x05; 32]);
+    let (vault_a, vault_a_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_a.as_ref()],
+        &program_id,
+    );
     let mut vault_a_account = Account::new(
         mollusk
             .sysvars
@@ -102,7 +124,10 @@ fn test_add_liquidity_success() {
     )
     .unwrap();
 
-    let vault_b = Pubkey::new_from_array([0x09; 32]);
+    let (vault_b, vault_b_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_b.as_ref()],
+        &program_id,
+    );
     let mut vault_b_account = Account::new(
         mollusk
             .sysvars
@@ -126,6 +151,12 @@ fn test_add_liquidity_success() {
     )
     .unwrap();
 
+    let (lp_vault, lp_vault_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), lp_mint.as_ref()],
+        &program_id,
+    );
+    let lp_vault_account = token_account(&mollusk, lp_mint, pool_pda, 0);
+
     let mut pool_account = Account::new(
         mollusk.sysvars.rent.minimum_balance(Pool::LEN),
         Pool::LEN,
@@ -143,7 +174,12 @@ fn test_add_liquidity_success() {
         fee_rate: 30,
         bump: pool_bump,
         lp_mint_bump,
-        _padding: [0; 4],
+        vault_a_bump,
+        vault_b_bump,
+        lp_vault_bump,
+        token_program_a: token_program.to_bytes(),
+        token_program_b: token_program.to_bytes(),
+        ..Zeroable::zeroed()
     };
     pool_account
         .data_as_mut_slice()
@@ -266,6 +302,11 @@ fn test_add_liquidity_success() {
             AccountMeta::new(user_token_b, false),
             AccountMeta::new(user_lp_token, false),
             AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(token_a, false),
+            AccountMeta::new_readonly(token_b, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new(lp_vault, false),
         ],
         data,
     };
@@ -283,10 +324,259 @@ fn test_add_liquidity_success() {
             (user_lp_token, user_lp_token_account),
             (token_a, mint_a_account),
             (token_b, mint_b_account),
-            (token_program, token_account),
+            (lp_vault, lp_vault_account),
+            (token_program, token_program_account),
         ],
         &[mollusk_svm::result::Check::success()],
     );
 
     assert!(!result.program_result.is_err());
+
+    // sqrt(50_000 * 50_000) LP, less the minimum liquidity locked in the LP vault.
+    let user_lp_token = result.get_account(&user_lp_token).unwrap();
+    assert_eq!(token_amount(user_lp_token), 50_000 - MINIMUM_LIQUIDITY);
+    let lp_vault = result.get_account(&lp_vault).unwrap();
+    assert_eq!(token_amount(lp_vault), MINIMUM_LIQUIDITY);
+}
+
+#[test]
+fn test_add_liquidity_insufficient_initial_liquidity() {
+    let (mollusk, pool) = TestPool::new(0, 0, 0);
+
+    // sqrt(1_000 * 1_000) does not exceed the minimum liquidity.
+    let ix = pool.instruction(
+        ix_data(1, &[1_000, 1_000, 0]),
+        pool.add_liquidity_accounts(),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(
+            AmmError::InsufficientInitialLiquidity,
+        ))],
+    );
+}
+
+#[test]
+fn test_add_liquidity_slippage_exceeded() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let ix = pool.instruction(
+        ix_data(1, &[50_000, 50_000, 50_001]),
+        pool.add_liquidity_accounts(),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::SlippageExceeded))],
+    );
+}
+
+#[test]
+fn test_add_liquidity_disabled() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.status = DEPOSITS_DISABLED;
+
+    let ix = pool.instruction(
+        ix_data(1, &[50_000, 50_000, 0]),
+        pool.add_liquidity_accounts(),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::DepositsDisabled))],
+    );
+}
+
+#[test]
+fn test_add_liquidity_zero_amount() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let ix = pool.instruction(ix_data(1, &[0, 50_000, 0]), pool.add_liquidity_accounts());
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::ZeroAmount))],
+    );
+}
+
+#[test]
+fn test_add_liquidity_deadline_exceeded() {
+    let (mut mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+    mollusk.sysvars.clock.unix_timestamp = 1_700_000_000;
+
+    let mut data = ix_data(1, &[50_000, 50_000, 0]);
+    data.push(DEADLINE_UNIX_TIMESTAMP);
+    data.extend_from_slice(&1_699_999_999u64.to_le_bytes());
+
+    mollusk.process_and_validate_instruction(
+        &pool.instruction(data, pool.add_liquidity_accounts()),
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::DeadlineExceeded))],
+    );
+}
+
+#[test]
+fn test_add_liquidity_fee_on_transfer() {
+    let (mollusk, pool) =
+        TestPool::with_token_programs(100_000, 100_000, 100_000, TOKEN_2022_ID, spl_token::ID);
+
+    // Token A charges 1% per transfer, so vault A only receives 9_900.
+    let mut accounts = pool.accounts(&mollusk);
+    set_account(
+        &mut accounts,
+        pool.token_a,
+        transfer_fee_mint(&mollusk, 100_000_000, 100),
+    );
+    set_account(
+        &mut accounts,
+        pool.vault_a,
+        transfer_fee_token_account(&mollusk, pool.token_a, pool.pool, 100_000),
+    );
+    set_account(
+        &mut accounts,
+        pool.user_token_a,
+        transfer_fee_token_account(&mollusk, pool.token_a, pool.user, pool.user_balance),
+    );
+
+    let ix = pool.instruction(
+        ix_data(1, &[10_000, 10_000, 9_900]),
+        pool.add_liquidity_accounts(),
+    );
+
+    let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+    let user_lp_token = result.get_account(&pool.user_lp_token).unwrap();
+    assert_eq!(token_amount(user_lp_token), pool.user_lp_balance + 9_900);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 109_900);
+    assert_eq!(pool_state.reserve_b, 110_000);
+}
+
+#[test]
+fn test_add_liquidity_ratio_optimal() {
+    let (mollusk, pool) = TestPool::new(100_000, 200_000, 100_000);
+
+    // Token B is offered in excess; only the 20_000 matching the reserves is used.
+    let ix = pool.instruction(
+        ix_data(1, &[10_000, 50_000, 10_000]),
+        pool.add_liquidity_accounts(),
+    );
+
+    let deposit = DepositAmounts {
+        amount_a: 10_000,
+        amount_b: 20_000,
+        lp_amount: 10_000,
+    };
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[
+            Check::success(),
+            Check::return_data(bytemuck::bytes_of(&deposit)),
+        ],
+    );
+
+    let user_token_b = result.get_account(&pool.user_token_b).unwrap();
+    assert_eq!(token_amount(user_token_b), pool.user_balance - 20_000);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 110_000);
+    assert_eq!(pool_state.reserve_b, 220_000);
+}
+
+#[test]
+fn test_add_liquidity_stable_swap_initial() {
+    let (mollusk, pool) = TestPool::stable_swap(0, 0, 0, 100);
+
+    let ix = pool.instruction(
+        ix_data(1, &[100_000, 150_000, 0]),
+        pool.add_liquidity_accounts(),
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    // The first deposit mints the invariant `D` of the amounts, which is
+    // below their sum once the pool is imbalanced.
+    let d = StableSwap { amp: 100 }.compute_d(100_000, 150_000).unwrap() as u64;
+    assert!(d < 250_000);
+    let user_lp_token = result.get_account(&pool.user_lp_token).unwrap();
+    assert_eq!(token_amount(user_lp_token), d - MINIMUM_LIQUIDITY);
+    let lp_vault = result.get_account(&pool.lp_vault).unwrap();
+    assert_eq!(token_amount(lp_vault), MINIMUM_LIQUIDITY);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 100_000);
+    assert_eq!(pool_state.reserve_b, 150_000);
+}
+
+#[test]
+fn test_add_liquidity_stable_swap() {
+    let (mollusk, pool) = TestPool::stable_swap(100_000, 150_000, 100_000, 100);
+
+    // Later deposits are proportional, as on a constant-product pool.
+    let ix = pool.instruction(
+        ix_data(1, &[10_000, 50_000, 10_000]),
+        pool.add_liquidity_accounts(),
+    );
+
+    let deposit = DepositAmounts {
+        amount_a: 10_000,
+        amount_b: 15_000,
+        lp_amount: 10_000,
+    };
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[
+            Check::success(),
+            Check::return_data(bytemuck::bytes_of(&deposit)),
+        ],
+    );
+
+    let user_lp_token = result.get_account(&pool.user_lp_token).unwrap();
+    assert_eq!(token_amount(user_lp_token), pool.user_lp_balance + 10_000);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 110_000);
+    assert_eq!(pool_state.reserve_b, 165_000);
+}
+
+#[test]
+fn test_calculate_deposit_amounts() {
+    // First deposit: both maximums set the price, whatever was donated to
+    // the reserves.
+    assert_eq!(
+        calculate_deposit_amounts(1_000, 3_000, 0, 0, 0).unwrap(),
+        (1_000, 3_000)
+    );
+    assert_eq!(
+        calculate_deposit_amounts(1_000, 3_000, 1, 0, 0).unwrap(),
+        (1_000, 3_000)
+    );
+    // Token B limits the deposit.
+    assert_eq!(
+        calculate_deposit_amounts(1_000, 1_000, 100, 200, 150).unwrap(),
+        (500, 1_000)
+    );
+    // Token A limits the deposit; the matched B amount rounds down.
+    assert_eq!(
+        calculate_deposit_amounts(1_000, 5_000, 300, 1_000, 500).unwrap(),
+        (1_000, 3_333)
+    );
+    assert_eq!(
+        calculate_deposit_amounts(u64::MAX, u64::MAX, u64::MAX, u64::MAX, u64::MAX).unwrap(),
+        (u64::MAX, u64::MAX)
+    );
 }
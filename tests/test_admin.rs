@@ -0,0 +1,313 @@
+mod common;
+
+use amm_pinocchio::{
+    constants::{DEPOSITS_DISABLED, MIN_RAMP_DURATION, POOL_STATUS_MASK, SWAPS_DISABLED},
+    error::AmmError,
+};
+use common::{TestPool, amm_error, config_account};
+use mollusk_svm::result::Check;
+use solana_sdk::{
+    account::Account, instruction::AccountMeta, program_error::ProgramError, pubkey::Pubkey,
+};
+
+fn admin_accounts(pool: &TestPool, signer: Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(signer, true),
+        AccountMeta::new(pool.pool, false),
+    ]
+}
+
+fn update_pool_data(fee_rate: u16, flash_loan_fee_rate: u16) -> Vec<u8> {
+    let mut data = vec![15];
+    data.extend_from_slice(&fee_rate.to_le_bytes());
+    data.extend_from_slice(&flash_loan_fee_rate.to_le_bytes());
+    data
+}
+
+#[test]
+fn test_update_pool_success() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.authority = pool.user.to_bytes();
+    pool.state.protocol_fee_rate = 1_000;
+
+    let ix = pool.instruction(update_pool_data(50, 9), admin_accounts(&pool, pool.user));
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.fee_rate, 50);
+    assert_eq!(pool_state.flash_loan_fee_rate, 9);
+    // The protocol's share is not the pool authority's to change.
+    assert_eq!(pool_state.protocol_fee_rate, 1_000);
+}
+
+#[test]
+fn test_update_pool_invalid_authority() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.authority = Pubkey::new_unique().to_bytes();
+
+    let ix = pool.instruction(update_pool_data(50, 0), admin_accounts(&pool, pool.user));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InvalidAuthority))],
+    );
+}
+
+#[test]
+fn test_update_pool_invalid_fee_rate() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.authority = pool.user.to_bytes();
+
+    let ix = pool.instruction(
+        update_pool_data(30, 10_001),
+        admin_accounts(&pool, pool.user),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InvalidFeeRate))],
+    );
+}
+
+#[test]
+fn test_set_protocol_fee_rate() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.authority = pool.user.to_bytes();
+    let protocol = Pubkey::new_unique();
+    let (config, config_state) = config_account(&mollusk, &pool.program_id, protocol);
+
+    let set_protocol_fee_rate = |signer: Pubkey, protocol_fee_rate: u16| {
+        let mut data = vec![23];
+        data.extend_from_slice(&protocol_fee_rate.to_le_bytes());
+        pool.instruction(
+            data,
+            vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new_readonly(config, false),
+                AccountMeta::new(pool.pool, false),
+            ],
+        )
+    };
+
+    let mut accounts = pool.accounts(&mollusk);
+    accounts.extend([(protocol, Account::default()), (config, config_state)]);
+
+    // The pool authority cannot zero the protocol's share.
+    mollusk.process_and_validate_instruction(
+        &set_protocol_fee_rate(pool.user, 0),
+        &accounts,
+        &[Check::err(amm_error(AmmError::InvalidAuthority))],
+    );
+
+    mollusk.process_and_validate_instruction(
+        &set_protocol_fee_rate(protocol, 10_001),
+        &accounts,
+        &[Check::err(amm_error(AmmError::InvalidFeeRate))],
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &set_protocol_fee_rate(protocol, 2_000),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.protocol_fee_rate, 2_000);
+}
+
+#[test]
+fn test_propose_and_accept_authority() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.authority = pool.user.to_bytes();
+    let new_authority = Pubkey::new_unique();
+
+    let mut data = vec![16];
+    data.extend_from_slice(new_authority.as_ref());
+    let propose = pool.instruction(data, admin_accounts(&pool, pool.user));
+
+    let mut accounts = pool.accounts(&mollusk);
+    accounts.push((new_authority, Account::default()));
+
+    let result = mollusk.process_and_validate_instruction(&propose, &accounts, &[Check::success()]);
+
+    // The proposal alone does not hand over the pool.
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.authority, pool.user.to_bytes());
+    assert_eq!(pool_state.pending_authority, new_authority.to_bytes());
+
+    let accounts: Vec<_> = accounts
+        .into_iter()
+        .map(|(key, account)| match result.get_account(&key) {
+            Some(updated) => (key, updated.clone()),
+            None => (key, account),
+        })
+        .collect();
+
+    let accept = pool.instruction(vec![17], admin_accounts(&pool, new_authority));
+    let result = mollusk.process_and_validate_instruction(&accept, &accounts, &[Check::success()]);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.authority, new_authority.to_bytes());
+    assert_eq!(pool_state.pending_authority, [0; 32]);
+}
+
+#[test]
+fn test_accept_authority_not_pending() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.pending_authority = Pubkey::new_unique().to_bytes();
+
+    let ix = pool.instruction(vec![17], admin_accounts(&pool, pool.user));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InvalidAuthority))],
+    );
+}
+
+#[test]
+fn test_set_pool_status_success() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.authority = pool.user.to_bytes();
+
+    let status = SWAPS_DISABLED | DEPOSITS_DISABLED;
+    let ix = pool.instruction(vec![18, status], admin_accounts(&pool, pool.user));
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.status, status);
+}
+
+#[test]
+fn test_set_pool_status_invalid_authority() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.authority = Pubkey::new_unique().to_bytes();
+
+    let ix = pool.instruction(vec![18, SWAPS_DISABLED], admin_accounts(&pool, pool.user));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InvalidAuthority))],
+    );
+}
+
+#[test]
+fn test_set_pool_status_unknown_bits() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.authority = pool.user.to_bytes();
+
+    let ix = pool.instruction(
+        vec![18, POOL_STATUS_MASK + 1],
+        admin_accounts(&pool, pool.user),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+}
+
+fn ramp_amp_data(target_amp: u64, ramp_stop_timestamp: i64) -> Vec<u8> {
+    let mut data = vec![20];
+    data.extend_from_slice(&target_amp.to_le_bytes());
+    data.extend_from_slice(&ramp_stop_timestamp.to_le_bytes());
+    data
+}
+
+/// A StableSwap pool at amplification 100, owned by `pool.user`.
+fn stable_pool() -> (mollusk_svm::Mollusk, TestPool) {
+    let (mut mollusk, mut pool) = TestPool::stable_swap(100_000, 100_000, 100_000, 100);
+    mollusk.sysvars.clock.unix_timestamp = 1_000_000;
+    pool.state.authority = pool.user.to_bytes();
+    (mollusk, pool)
+}
+
+#[test]
+fn test_ramp_amp_success() {
+    let (mollusk, pool) = stable_pool();
+    let now = mollusk.sysvars.clock.unix_timestamp;
+    let stop = now + MIN_RAMP_DURATION;
+
+    let ix = pool.instruction(ramp_amp_data(1_000, stop), admin_accounts(&pool, pool.user));
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.initial_amp, 100);
+    assert_eq!(pool_state.target_amp, 1_000);
+    assert_eq!(pool_state.ramp_start_timestamp, now);
+    assert_eq!(pool_state.ramp_stop_timestamp, stop);
+
+    // Halfway through the ramp, the coefficient is halfway to the target.
+    assert_eq!(pool_state.amp(now + MIN_RAMP_DURATION / 2), 550);
+    assert_eq!(pool_state.amp(stop), 1_000);
+}
+
+#[test]
+fn test_ramp_amp_invalid_amp() {
+    let (mollusk, pool) = stable_pool();
+    let stop = mollusk.sysvars.clock.unix_timestamp + MIN_RAMP_DURATION;
+
+    // More than a tenfold change either way.
+    for target_amp in [1_001, 9, 0] {
+        let ix = pool.instruction(
+            ramp_amp_data(target_amp, stop),
+            admin_accounts(&pool, pool.user),
+        );
+
+        mollusk.process_and_validate_instruction(
+            &ix,
+            &pool.accounts(&mollusk),
+            &[Check::err(amm_error(AmmError::InvalidAmp))],
+        );
+    }
+}
+
+#[test]
+fn test_ramp_amp_too_short() {
+    let (mollusk, pool) = stable_pool();
+    let stop = mollusk.sysvars.clock.unix_timestamp + MIN_RAMP_DURATION - 1;
+
+    let ix = pool.instruction(ramp_amp_data(200, stop), admin_accounts(&pool, pool.user));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InvalidRampDuration))],
+    );
+}
+
+#[test]
+fn test_ramp_amp_constant_product() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.authority = pool.user.to_bytes();
+
+    let ix = pool.instruction(
+        ramp_amp_data(200, MIN_RAMP_DURATION),
+        admin_accounts(&pool, pool.user),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InvalidCurveType))],
+    );
+}
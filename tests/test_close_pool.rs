@@ -0,0 +1,274 @@
+mod common;
+
+use amm_pinocchio::{constants::CONFIG_SEED, curve::CURVE_CONSTANT_PRODUCT, error::AmmError};
+use common::{TestPool, amm_error, config_account, ix_data, token_amount};
+use mollusk_svm::{program, result::Check};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+fn close_pool_accounts(pool: &TestPool, authority: Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(authority, true),
+        AccountMeta::new(pool.pool, false),
+        AccountMeta::new_readonly(pool.lp_mint, false),
+        AccountMeta::new(pool.vault_a, false),
+        AccountMeta::new(pool.vault_b, false),
+        AccountMeta::new(pool.lp_vault, false),
+        AccountMeta::new(pool.user, false),
+        AccountMeta::new(pool.user_token_a, false),
+        AccountMeta::new(pool.user_token_b, false),
+        AccountMeta::new_readonly(pool.token_a, false),
+        AccountMeta::new_readonly(pool.token_b, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new_readonly(pool.token_program_a, false),
+        AccountMeta::new_readonly(pool.token_program_b, false),
+    ]
+}
+
+#[test]
+fn test_close_pool_success() {
+    let (mollusk, mut pool) = TestPool::new(0, 0, 0);
+    pool.state.authority = pool.user.to_bytes();
+
+    let accounts = pool.accounts(&mollusk);
+    let rent: u64 = accounts
+        .iter()
+        .filter(|(key, _)| [pool.pool, pool.vault_a, pool.vault_b, pool.lp_vault].contains(key))
+        .map(|(_, account)| account.lamports)
+        .sum();
+    let user_lamports = accounts
+        .iter()
+        .find(|(key, _)| *key == pool.user)
+        .unwrap()
+        .1
+        .lamports;
+
+    let ix = pool.instruction(vec![19], close_pool_accounts(&pool, pool.user));
+
+    let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+    for closed in [pool.pool, pool.vault_a, pool.vault_b, pool.lp_vault] {
+        let account = result.get_account(&closed).unwrap();
+        assert_eq!(account.lamports, 0);
+        assert!(account.data.is_empty());
+    }
+    assert_eq!(
+        result.get_account(&pool.user).unwrap().lamports,
+        user_lamports + rent
+    );
+}
+
+#[test]
+fn test_close_pool_after_full_withdrawal() {
+    let (mollusk, mut pool) = TestPool::new(0, 0, 0);
+    pool.state.authority = pool.user.to_bytes();
+
+    // The first deposit locks `MINIMUM_LIQUIDITY` of its 100_000 LP tokens;
+    // the user then withdraws everything else.
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (
+                &pool.instruction(
+                    ix_data(1, &[100_000, 100_000, 0]),
+                    pool.add_liquidity_accounts(),
+                ),
+                &[Check::success()],
+            ),
+            (
+                &pool.instruction(ix_data(3, &[99_000, 0, 0]), pool.withdraw_accounts()),
+                &[Check::success()],
+            ),
+            (
+                &pool.instruction(vec![19], close_pool_accounts(&pool, pool.user)),
+                &[Check::success()],
+            ),
+        ],
+        &pool.accounts(&mollusk),
+    );
+
+    // The locked LP tokens are burned and the reserves backing them are paid
+    // out, so the user gets back the whole deposit.
+    let lp_mint = result.get_account(&pool.lp_mint).unwrap();
+    assert_eq!(
+        spl_token::state::Mint::unpack(&lp_mint.data)
+            .unwrap()
+            .supply,
+        0
+    );
+    for (token_account, closed) in [
+        (pool.user_token_a, pool.vault_a),
+        (pool.user_token_b, pool.vault_b),
+    ] {
+        assert_eq!(
+            token_amount(result.get_account(&token_account).unwrap()),
+            pool.user_balance
+        );
+        assert_eq!(result.get_account(&closed).unwrap().lamports, 0);
+    }
+    for closed in [pool.pool, pool.lp_vault] {
+        let account = result.get_account(&closed).unwrap();
+        assert_eq!(account.lamports, 0);
+        assert!(account.data.is_empty());
+    }
+}
+
+/// `Initialize` of a new constant-product pool at the address of `pool`.
+fn reinitialize_ix(pool: &TestPool, lp_decimals: u8) -> Instruction {
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let config = config_account_address(pool);
+
+    let mut data = vec![0];
+    data.extend_from_slice(&30u16.to_le_bytes());
+    data.extend_from_slice(&9u16.to_le_bytes());
+    data.extend_from_slice(&[
+        pool.state.bump,
+        pool.state.lp_mint_bump,
+        pool.state.vault_a_bump,
+        pool.state.vault_b_bump,
+        pool.state.lp_vault_bump,
+        lp_decimals,
+        CURVE_CONSTANT_PRODUCT,
+    ]);
+    data.extend_from_slice(&0u64.to_le_bytes());
+
+    pool.instruction(
+        data,
+        vec![
+            AccountMeta::new(pool.user, true),
+            AccountMeta::new(pool.pool, false),
+            AccountMeta::new_readonly(pool.token_a, false),
+            AccountMeta::new_readonly(pool.token_b, false),
+            AccountMeta::new(pool.lp_mint, false),
+            AccountMeta::new(pool.vault_a, false),
+            AccountMeta::new(pool.vault_b, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(pool.token_program_a, false),
+            AccountMeta::new_readonly(pool.token_program_b, false),
+            AccountMeta::new(pool.lp_vault, false),
+            AccountMeta::new_readonly(config, false),
+        ],
+    )
+}
+
+fn config_account_address(pool: &TestPool) -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG_SEED.as_bytes()], &pool.program_id).0
+}
+
+#[test]
+fn test_close_pool_then_reinitialize() {
+    let (mollusk, mut pool) = TestPool::new(0, 0, 0);
+    pool.state.authority = pool.user.to_bytes();
+
+    let mut accounts = pool.accounts(&mollusk);
+    accounts.push(config_account(
+        &mollusk,
+        &pool.program_id,
+        Pubkey::new_unique(),
+    ));
+
+    let ix = pool.instruction(vec![19], close_pool_accounts(&pool, pool.user));
+    let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+    // The runtime hands closed accounts back to the system program once the
+    // transaction ends.
+    let accounts: Vec<_> = accounts
+        .into_iter()
+        .map(|(key, account)| match result.get_account(&key) {
+            Some(updated) if updated.lamports == 0 => (key, Account::default()),
+            Some(updated) => (key, updated.clone()),
+            None => (key, account),
+        })
+        .collect();
+
+    // The LP mint left behind keeps its decimals.
+    mollusk.process_and_validate_instruction(
+        &reinitialize_ix(&pool, 9),
+        &accounts,
+        &[Check::err(amm_error(AmmError::InvalidLpDecimals))],
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &reinitialize_ix(&pool, 6),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.authority, pool.user.to_bytes());
+    assert_eq!(pool_state.lp_mint, pool.lp_mint.to_bytes());
+    assert_eq!(pool_state.reserve_a, 0);
+    assert_eq!(pool_state.reserve_b, 0);
+}
+
+#[test]
+fn test_reinitialize_with_outstanding_lp_supply() {
+    // LP tokens still in circulation keep the old LP mint from being reused.
+    let (mollusk, pool) = TestPool::new(0, 0, 1_000);
+
+    let mut accounts = pool.accounts(&mollusk);
+    accounts
+        .retain(|(key, _)| ![pool.pool, pool.vault_a, pool.vault_b, pool.lp_vault].contains(key));
+    accounts.extend([
+        (pool.pool, Account::default()),
+        (pool.vault_a, Account::default()),
+        (pool.vault_b, Account::default()),
+        (pool.lp_vault, Account::default()),
+        config_account(&mollusk, &pool.program_id, Pubkey::new_unique()),
+    ]);
+
+    mollusk.process_and_validate_instruction(
+        &reinitialize_ix(&pool, 6),
+        &accounts,
+        &[Check::err(ProgramError::AccountAlreadyInitialized)],
+    );
+}
+
+#[test]
+fn test_close_pool_not_empty() {
+    let (mollusk, mut pool) = TestPool::new(0, 0, 0);
+    pool.state.authority = pool.user.to_bytes();
+    pool.state.protocol_fees_a = 1;
+
+    let ix = pool.instruction(vec![19], close_pool_accounts(&pool, pool.user));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::PoolNotEmpty))],
+    );
+}
+
+#[test]
+fn test_close_pool_outstanding_lp_supply() {
+    // LP tokens held outside the LP vault are still owed their share.
+    let (mollusk, mut pool) = TestPool::new(1_000, 1_000, 1_000);
+    pool.state.authority = pool.user.to_bytes();
+
+    let ix = pool.instruction(vec![19], close_pool_accounts(&pool, pool.user));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::PoolNotEmpty))],
+    );
+}
+
+#[test]
+fn test_close_pool_invalid_authority() {
+    let (mollusk, mut pool) = TestPool::new(0, 0, 0);
+    pool.state.authority = Pubkey::new_unique().to_bytes();
+
+    let ix = pool.instruction(vec![19], close_pool_accounts(&pool, pool.user));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InvalidAuthority))],
+    );
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use amm_pinocchio::{constants::CONFIG_SEED, error::AmmError};
+use common::{TestPool, amm_error, config_account, token_account, token_amount};
+use mollusk_svm::{Mollusk, program, result::Check};
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey};
+
+/// Protocol authority recorded in the protocol config.
+const PROTOCOL: Pubkey = Pubkey::new_from_array([0x50; 32]);
+
+fn collect_accounts(
+    pool: &TestPool,
+    authority: Pubkey,
+    treasury_a: Pubkey,
+    treasury_b: Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(authority, true),
+        AccountMeta::new_readonly(config_account_address(pool), false),
+        AccountMeta::new(pool.pool, false),
+        AccountMeta::new(pool.vault_a, false),
+        AccountMeta::new(pool.vault_b, false),
+        AccountMeta::new(treasury_a, false),
+        AccountMeta::new(treasury_b, false),
+        AccountMeta::new_readonly(pool.token_a, false),
+        AccountMeta::new_readonly(pool.token_b, false),
+        AccountMeta::new_readonly(pool.token_program_a, false),
+        AccountMeta::new_readonly(pool.token_program_b, false),
+    ]
+}
+
+fn config_account_address(pool: &TestPool) -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG_SEED.as_bytes()], &pool.program_id).0
+}
+
+/// The accounts of `pool` with accrued protocol fees of 150 token A and 70
+/// token B in its vaults, plus the protocol config, the protocol authority and
+/// two treasury token accounts owned by `treasury_owner`.
+fn collect_setup(
+    mollusk: &Mollusk,
+    pool: &mut TestPool,
+    treasury_owner: Pubkey,
+) -> (Vec<(Pubkey, Account)>, Pubkey, Pubkey) {
+    pool.state.protocol_fees_a = 150;
+    pool.state.protocol_fees_b = 70;
+
+    let (treasury_a, treasury_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (system_program, _) = program::keyed_account_for_system_program();
+
+    let mut accounts = pool.accounts(mollusk);
+    accounts.retain(|(key, _)| *key != pool.vault_a && *key != pool.vault_b);
+    accounts.extend([
+        (
+            pool.vault_a,
+            token_account(mollusk, pool.token_a, pool.pool, 100_150),
+        ),
+        (
+            pool.vault_b,
+            token_account(mollusk, pool.token_b, pool.pool, 100_070),
+        ),
+        config_account(mollusk, &pool.program_id, PROTOCOL),
+        (PROTOCOL, Account::new(1_000_000_000, 0, &system_program)),
+        (
+            treasury_a,
+            token_account(mollusk, pool.token_a, treasury_owner, 0),
+        ),
+        (
+            treasury_b,
+            token_account(mollusk, pool.token_b, treasury_owner, 0),
+        ),
+    ]);
+
+    (accounts, treasury_a, treasury_b)
+}
+
+#[test]
+fn test_collect_protocol_fees_success() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    let (accounts, treasury_a, treasury_b) = collect_setup(&mollusk, &mut pool, PROTOCOL);
+
+    let ix = pool.instruction(
+        vec![7],
+        collect_accounts(&pool, PROTOCOL, treasury_a, treasury_b),
+    );
+
+    let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+    assert_eq!(token_amount(result.get_account(&treasury_a).unwrap()), 150);
+    assert_eq!(token_amount(result.get_account(&treasury_b).unwrap()), 70);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.protocol_fees_a, 0);
+    assert_eq!(pool_state.protocol_fees_b, 0);
+    assert_eq!(pool_state.reserve_a, 100_000);
+    assert_eq!(pool_state.reserve_b, 100_000);
+}
+
+#[test]
+fn test_collect_protocol_fees_invalid_authority() {
+    // The pool authority is not the protocol and cannot collect.
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.authority = pool.user.to_bytes();
+    let user = pool.user;
+    let (accounts, treasury_a, treasury_b) = collect_setup(&mollusk, &mut pool, user);
+
+    let ix = pool.instruction(
+        vec![7],
+        collect_accounts(&pool, pool.user, treasury_a, treasury_b),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_error(AmmError::InvalidAuthority))],
+    );
+}
+
+#[test]
+fn test_collect_protocol_fees_invalid_treasury() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    let user = pool.user;
+    let (accounts, treasury_a, treasury_b) = collect_setup(&mollusk, &mut pool, user);
+
+    let ix = pool.instruction(
+        vec![7],
+        collect_accounts(&pool, PROTOCOL, treasury_a, treasury_b),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_error(AmmError::InvalidTreasury))],
+    );
+}
+
+#[test]
+fn test_collect_protocol_fees_invalid_config() {
+    // A program-owned account with config data, but not at the config PDA.
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    let (mut accounts, treasury_a, treasury_b) = collect_setup(&mollusk, &mut pool, PROTOCOL);
+    let fake_config = Pubkey::new_unique();
+    let (_, config) = config_account(&mollusk, &pool.program_id, PROTOCOL);
+    accounts.push((fake_config, config));
+
+    let mut metas = collect_accounts(&pool, PROTOCOL, treasury_a, treasury_b);
+    metas[1] = AccountMeta::new_readonly(fake_config, false);
+
+    mollusk.process_and_validate_instruction(
+        &pool.instruction(vec![7], metas),
+        &accounts,
+        &[Check::err(amm_error(AmmError::InvalidConfigAddress))],
+    );
+}
@@ -0,0 +1,227 @@
+use amm_pinocchio::{
+    constants::{MAX_AMP, MIN_AMP},
+    curve::{
+        CURVE_CONSTANT_PRODUCT, CURVE_STABLE_SWAP, ConstantProduct, Curve, StableSwap, SwapCurve,
+    },
+    error::AmmError,
+    helper::{calculate_swap_input, calculate_swap_output},
+};
+use pinocchio::program_error::ProgramError;
+
+#[test]
+fn test_curve_from_type() {
+    assert_eq!(
+        Curve::new(CURVE_CONSTANT_PRODUCT, 0),
+        Ok(Curve::ConstantProduct(ConstantProduct))
+    );
+    assert_eq!(
+        Curve::new(CURVE_STABLE_SWAP, 100),
+        Ok(Curve::StableSwap(StableSwap { amp: 100 }))
+    );
+    assert_eq!(
+        Curve::new(u8::MAX, 0),
+        Err(ProgramError::Custom(AmmError::InvalidCurveType as u32))
+    );
+}
+
+#[test]
+fn test_curve_amp_range() {
+    let invalid_amp = Err(ProgramError::Custom(AmmError::InvalidAmp as u32));
+
+    assert!(Curve::new(CURVE_STABLE_SWAP, MIN_AMP).is_ok());
+    assert!(Curve::new(CURVE_STABLE_SWAP, MAX_AMP).is_ok());
+    assert_eq!(Curve::new(CURVE_STABLE_SWAP, MIN_AMP - 1), invalid_amp);
+    assert_eq!(Curve::new(CURVE_STABLE_SWAP, MAX_AMP + 1), invalid_amp);
+    assert_eq!(Curve::new(CURVE_CONSTANT_PRODUCT, 1), invalid_amp);
+}
+
+#[test]
+fn test_constant_product_swaps() {
+    let curve = Curve::new(CURVE_CONSTANT_PRODUCT, 0).unwrap();
+
+    assert_eq!(
+        curve.swap_out(10_000, 100_000, 200_000, 30),
+        calculate_swap_output(10_000, 100_000, 200_000, 30)
+    );
+    assert_eq!(
+        curve.swap_in(10_000, 100_000, 200_000, 30),
+        calculate_swap_input(10_000, 100_000, 200_000, 30)
+    );
+}
+
+#[test]
+fn test_constant_product_deposit() {
+    let curve = ConstantProduct;
+
+    // The first deposit mints the geometric mean.
+    assert_eq!(curve.deposit(40_000, 90_000, 0, 0, 0), Ok(60_000));
+    // Donations in the reserves do not count towards it.
+    assert_eq!(curve.deposit(40_000, 90_000, 1, 0, 0), Ok(60_000));
+
+    // Later deposits mint the smaller proportional share, rounded down.
+    assert_eq!(
+        curve.deposit(10_000, 30_000, 100_000, 200_000, 150_000),
+        Ok(15_000)
+    );
+    assert_eq!(curve.deposit(1, 1, 3, 3, 2), Ok(0));
+}
+
+#[test]
+fn test_constant_product_withdraw() {
+    let curve = ConstantProduct;
+
+    assert_eq!(
+        curve.withdraw(15_000, 100_000, 200_000, 150_000),
+        Ok((10_000, 20_000))
+    );
+    assert_eq!(curve.withdraw(1, 2, 5, 3), Ok((0, 1)));
+}
+
+#[test]
+fn test_stable_swap_invariant() {
+    // A balanced pool's invariant is the sum of its reserves.
+    assert_eq!(
+        StableSwap { amp: 100 }.compute_d(1_000_000, 1_000_000),
+        Ok(2_000_000)
+    );
+    assert_eq!(StableSwap { amp: 1 }.compute_d(0, 0), Ok(0));
+
+    // Checked against a reference implementation of the Curve contract.
+    assert_eq!(
+        StableSwap { amp: 100 }.compute_d(1_000_000, 3_000_000),
+        Ok(3_996_691)
+    );
+    assert_eq!(
+        StableSwap { amp: MAX_AMP }.compute_d(u64::MAX, u64::MAX),
+        Ok(2 * u64::MAX as u128)
+    );
+
+    // An imbalanced pool sits between the constant-product and
+    // constant-sum invariants.
+    for amp in [1, 10, 100, 1_000, MAX_AMP] {
+        let d = StableSwap { amp }.compute_d(1_000_000, 9_000_000).unwrap();
+        assert!((6_000_000..10_000_000).contains(&d));
+    }
+}
+
+#[test]
+fn test_stable_swap_out() {
+    let curve = StableSwap { amp: 100 };
+
+    // Close to 1:1 on a balanced pool, where constant product pays 996_006.
+    assert_eq!(
+        curve.swap_out(1_000_000, 1_000_000_000, 1_000_000_000, 30),
+        Ok((996_995, 3_000))
+    );
+    assert_eq!(
+        curve.swap_out(
+            1_000_000_000_000,
+            1_000_000_000_000_000,
+            1_000_000_000_000_000,
+            4
+        ),
+        Ok((999_595_028_874, 400_000_000))
+    );
+    assert_eq!(
+        StableSwap { amp: 10 }.swap_out(500_000, 1_000_000, 3_000_000, 30),
+        Ok((523_459, 1_500))
+    );
+
+    // A higher amplification coefficient pays more on a balanced pool.
+    let mut previous = 0;
+    for amp in [1, 10, 100, 1_000, MAX_AMP] {
+        let (amount_out, _) = StableSwap { amp }
+            .swap_out(100_000, 1_000_000, 1_000_000, 0)
+            .unwrap();
+        assert!(amount_out > previous && amount_out < 100_000);
+        previous = amount_out;
+    }
+}
+
+#[test]
+fn test_stable_swap_large_reserves() {
+    let curve = StableSwap { amp: 100 };
+    let reserve = 10_000_000_000_000_000_000;
+
+    // `D` is above 2^64, so the reserve Newton step squares a value beyond
+    // 2^64.
+    assert!(curve.compute_d(reserve, reserve).unwrap() > u64::MAX as u128);
+
+    let (amount_out, fee) = curve
+        .swap_out(1_000_000_000_000_000, reserve, reserve, 30)
+        .unwrap();
+    assert_eq!(fee, 3_000_000_000_000);
+    assert!(amount_out > 996_000_000_000_000 && amount_out < 997_000_000_000_000);
+
+    let (amount_in, _) = curve.swap_in(amount_out, reserve, reserve, 30).unwrap();
+    assert!(amount_in <= 1_000_000_000_000_000);
+}
+
+#[test]
+fn test_stable_swap_in_covers_output() {
+    let curve = StableSwap { amp: 85 };
+    let (reserve_in, reserve_out) = (40_000_000_000, 70_000_000_000);
+
+    for amount_out in (1..reserve_out - 1).step_by(699_999_999) {
+        let (amount_in, fee) = curve
+            .swap_in(amount_out, reserve_in, reserve_out, 30)
+            .unwrap();
+
+        let (paid, swap_fee) = curve
+            .swap_out(amount_in, reserve_in, reserve_out, 30)
+            .unwrap();
+        assert!(paid >= amount_out);
+        assert_eq!(swap_fee, fee);
+
+        // No smaller input covers it.
+        let (short, _) = curve
+            .swap_out(amount_in - 1, reserve_in, reserve_out, 30)
+            .unwrap();
+        assert!(short < amount_out);
+    }
+
+    assert_eq!(
+        curve.swap_in(reserve_out - 1, reserve_in, reserve_out, 30),
+        Err(ProgramError::Custom(AmmError::InsufficientLiquidity as u32))
+    );
+}
+
+#[test]
+fn test_stable_swap_deposit() {
+    let curve = StableSwap { amp: 100 };
+
+    // The first deposit mints the invariant.
+    assert_eq!(curve.deposit(1_000_000, 3_000_000, 0, 0, 0), Ok(3_996_691));
+
+    // Later deposits mint the smaller proportional share, as for constant
+    // product.
+    assert_eq!(
+        curve.deposit(10_000, 30_000, 100_000, 200_000, 150_000),
+        ConstantProduct.deposit(10_000, 30_000, 100_000, 200_000, 150_000)
+    );
+}
+
+#[test]
+fn test_spot_price() {
+    // Constant product prices by the reserve ratio.
+    assert_eq!(ConstantProduct.spot_price(100_000, 400_000), Ok(4 << 64));
+    assert_eq!(ConstantProduct.spot_price(400_000, 100_000), Ok(1 << 62));
+
+    // StableSwap matches the price of a small trade on an imbalanced pool,
+    // close to 1 rather than to the 2:3 reserve ratio.
+    let curve = StableSwap { amp: 100 };
+    let (reserve_a, reserve_b) = (600_000_000_000, 400_000_000_000);
+    let probe = 1_000_000;
+    for (reserve_base, reserve_quote) in [(reserve_a, reserve_b), (reserve_b, reserve_a)] {
+        let price = curve.spot_price(reserve_base, reserve_quote).unwrap();
+        let (amount_out, _) = curve
+            .swap_out(probe, reserve_base, reserve_quote, 0)
+            .unwrap();
+        let expected = ((price * probe as u128) >> 64) as u64;
+        assert!(expected.abs_diff(amount_out) <= 10);
+    }
+
+    let price_a = curve.spot_price(reserve_a, reserve_b).unwrap();
+    assert!(price_a > (99 << 64) / 100 && price_a < 1 << 64);
+    assert_eq!(curve.spot_price(1_000, 1_000), Ok(1 << 64));
+}
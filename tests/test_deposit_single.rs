@@ -0,0 +1,143 @@
+use mollusk_svm::result::Check;
+
+mod common;
+
+use amm_pinocchio::error::AmmError;
+use amm_pinocchio::helper::{calculate_single_deposit_swap, calculate_swap_output};
+use common::{TestPool, amm_error, ix_data, token_amount};
+
+#[test]
+fn test_deposit_single_success() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let swap_amount = calculate_single_deposit_swap(10_000, 100_000, 30).unwrap();
+    let (swap_out, _) = calculate_swap_output(swap_amount, 100_000, 100_000, 30).unwrap();
+    let lp_in = (10_000 - swap_amount) * 100_000 / (100_000 + swap_amount);
+    let lp_out = swap_out * 100_000 / (100_000 - swap_out);
+    let expected_lp = lp_in.min(lp_out);
+
+    let ix = pool.instruction(
+        ix_data(13, &[10_000, expected_lp]),
+        pool.deposit_single_accounts(pool.side_a()),
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    let user_token_a = result.get_account(&pool.user_token_a).unwrap();
+    assert_eq!(token_amount(user_token_a), pool.user_balance - 10_000);
+    let user_token_b = result.get_account(&pool.user_token_b).unwrap();
+    assert_eq!(token_amount(user_token_b), pool.user_balance);
+    let user_lp_token = result.get_account(&pool.user_lp_token).unwrap();
+    assert_eq!(
+        token_amount(user_lp_token),
+        pool.user_lp_balance + expected_lp
+    );
+
+    // The whole deposit ends up in reserve A; reserve B is unchanged.
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 110_000);
+    assert_eq!(pool_state.reserve_b, 100_000);
+}
+
+#[test]
+fn test_deposit_single_slippage_exceeded() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    // Depositing 10% of one side is worth less than 5% of the pool.
+    let ix = pool.instruction(
+        ix_data(13, &[10_000, 5_000]),
+        pool.deposit_single_accounts(pool.side_b()),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::SlippageExceeded))],
+    );
+}
+
+#[test]
+fn test_deposit_single_empty_pool() {
+    let (mollusk, pool) = TestPool::new(0, 0, 0);
+
+    let ix = pool.instruction(
+        ix_data(13, &[10_000, 0]),
+        pool.deposit_single_accounts(pool.side_a()),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::EmptyPool))],
+    );
+}
+
+#[test]
+fn test_deposit_single_stable_swap() {
+    let (mollusk, pool) = TestPool::stable_swap(100_000, 100_000, 100_000, 100);
+
+    let ix = pool.instruction(
+        ix_data(13, &[10_000, 0]),
+        pool.deposit_single_accounts(pool.side_a()),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InvalidCurveType))],
+    );
+}
+
+/// After the swap, the rest of the deposit and the swap output must be in the
+/// ratio of the new reserves, up to rounding.
+fn assert_balanced(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_rate: u16) {
+    let swap_amount = calculate_single_deposit_swap(amount_in, reserve_in, fee_rate).unwrap();
+    let (swap_out, _) =
+        calculate_swap_output(swap_amount, reserve_in, reserve_out, fee_rate).unwrap();
+
+    // Output the rest of the deposit would have to be paired with.
+    let deposit_in = (amount_in - swap_amount) as f64;
+    let balanced_out =
+        deposit_in * (reserve_out - swap_out) as f64 / (reserve_in as f64 + swap_amount as f64);
+
+    let error = (balanced_out - swap_out as f64).abs();
+    assert!(
+        error <= 2.0 + swap_out as f64 * 1e-9,
+        "{amount_in} into {reserve_in}/{reserve_out}: {swap_out} vs {balanced_out}"
+    );
+}
+
+#[test]
+fn test_calculate_single_deposit_swap() {
+    // Without a fee, half of a small deposit is swapped.
+    assert_eq!(
+        calculate_single_deposit_swap(2, 1_000_000_000, 0).unwrap(),
+        0
+    );
+    assert_eq!(
+        calculate_single_deposit_swap(2_000, 1_000_000_000_000, 0).unwrap(),
+        999
+    );
+
+    assert_balanced(10_000, 100_000, 100_000, 30);
+    assert_balanced(1_000_000, 10_000_000, 5_000_000, 30);
+    assert_balanced(50_000_000, 80_000_000, 3_000_000, 100);
+
+    // Large reserves take the approximate square root path.
+    for (amount_in, reserve_in) in [(1 << 60, 1 << 62), (u64::MAX / 4, u64::MAX / 2)] {
+        let (a, r, f) = (amount_in as f64, reserve_in as f64, 0.997);
+        let expected =
+            ((r * r * (1.0 + f) * (1.0 + f) + 4.0 * f * a * r).sqrt() - r * (1.0 + f)) / (2.0 * f);
+        let swap_amount = calculate_single_deposit_swap(amount_in, reserve_in, 30).unwrap();
+        assert!((swap_amount as f64 - expected).abs() / expected < 1e-9);
+    }
+
+    assert_eq!(
+        calculate_single_deposit_swap(1, 1, 10_000),
+        Err(pinocchio::program_error::ProgramError::ArithmeticOverflow)
+    );
+}
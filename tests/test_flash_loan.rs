@@ -0,0 +1,126 @@
+mod common;
+
+use amm_pinocchio::{constants::SWAPS_DISABLED, error::AmmError, helper::calculate_flash_loan_fee};
+use common::{TestPool, amm_error, ix_data, token_amount};
+use mollusk_svm::result::Check;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    sysvar,
+};
+
+fn borrow_ix(pool: &TestPool, amount: u64) -> Instruction {
+    pool.instruction(
+        ix_data(9, &[amount]),
+        vec![
+            AccountMeta::new(pool.user, true),
+            AccountMeta::new(pool.pool, false),
+            AccountMeta::new(pool.vault_a, false),
+            AccountMeta::new_readonly(pool.token_a, false),
+            AccountMeta::new(pool.user_token_a, false),
+            AccountMeta::new_readonly(sysvar::instructions::ID, false),
+            AccountMeta::new_readonly(pool.token_program_a, false),
+        ],
+    )
+}
+
+fn repay_ix(pool: &TestPool) -> Instruction {
+    pool.instruction(
+        vec![10],
+        vec![
+            AccountMeta::new(pool.user, true),
+            AccountMeta::new(pool.pool, false),
+            AccountMeta::new(pool.vault_a, false),
+            AccountMeta::new_readonly(pool.token_a, false),
+            AccountMeta::new(pool.user_token_a, false),
+            AccountMeta::new_readonly(pool.token_program_a, false),
+        ],
+    )
+}
+
+#[test]
+fn test_flash_loan_success() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.flash_loan_fee_rate = 9;
+
+    let accounts = pool.accounts(&mollusk);
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&borrow_ix(&pool, 50_000), &[Check::success()]),
+            (&repay_ix(&pool), &[Check::success()]),
+        ],
+        &accounts,
+    );
+
+    // 50_000 * 9 / 10000 = 45
+    let vault_a = result.get_account(&pool.vault_a).unwrap();
+    let user_token_a = result.get_account(&pool.user_token_a).unwrap();
+    assert_eq!(token_amount(vault_a), 100_045);
+    assert_eq!(token_amount(user_token_a), 1_000_000 - 45);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 100_045);
+    assert_eq!(pool_state.reserve_b, 100_000);
+    assert_eq!(pool_state.flash_loan_a, 0);
+}
+
+#[test]
+fn test_flash_borrow_without_repay() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    mollusk.process_and_validate_instruction_chain(
+        &[(
+            &borrow_ix(&pool, 50_000),
+            &[Check::err(amm_error(AmmError::MissingFlashRepay))],
+        )],
+        &pool.accounts(&mollusk),
+    );
+}
+
+#[test]
+fn test_flash_borrow_disabled() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.status = SWAPS_DISABLED;
+
+    mollusk.process_and_validate_instruction_chain(
+        &[(
+            &borrow_ix(&pool, 50_000),
+            &[Check::err(amm_error(AmmError::SwapsDisabled))],
+        )],
+        &pool.accounts(&mollusk),
+    );
+}
+
+#[test]
+fn test_flash_borrow_twice_before_repay() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&borrow_ix(&pool, 10_000), &[Check::success()]),
+            (
+                &borrow_ix(&pool, 10_000),
+                &[Check::err(amm_error(AmmError::FlashLoanActive))],
+            ),
+        ],
+        &pool.accounts(&mollusk),
+    );
+}
+
+#[test]
+fn test_flash_repay_without_loan() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    mollusk.process_and_validate_instruction(
+        &repay_ix(&pool),
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::NoFlashLoan))],
+    );
+}
+
+#[test]
+fn test_flash_loan_fee_rounds_up() {
+    assert_eq!(calculate_flash_loan_fee(50_000, 9).unwrap(), 45);
+    assert_eq!(calculate_flash_loan_fee(1, 9).unwrap(), 1);
+    assert_eq!(calculate_flash_loan_fee(1_000, 0).unwrap(), 0);
+    assert_eq!(calculate_flash_loan_fee(u64::MAX, 10000).unwrap(), u64::MAX);
+}
@@ -7,8 +7,18 @@ use {
     },
 };
 
-use amm_pinocchio::constants::{LP_MINT_SEED, POOL_SEED};
-use mollusk_svm::program;
+mod common;
+
+use amm_pinocchio::constants::{LP_MINT_SEED, MAX_AMP, MAX_LP_DECIMALS, POOL_SEED, VAULT_SEED};
+use amm_pinocchio::curve::{CURVE_CONSTANT_PRODUCT, CURVE_STABLE_SWAP};
+use amm_pinocchio::error::AmmError;
+use amm_pinocchio::states::Pool;
+use bytemuck::Zeroable;
+use common::{
+    PROTOCOL_FEE_RATE, TOKEN_2022_ID, amm_error, config_account, mint_account, vault_address,
+    with_owner,
+};
+use mollusk_svm::{program, result::Check};
 use solana_sdk::{
     account::{Account, WritableAccount},
     program_option::COption,
@@ -75,67 +85,49 @@ fn test_initialize_success() {
     .unwrap();
 
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
-        &[POOL_SEED.as_bytes(), token_a.as_ref(), token_b.as_ref()],
+        &[
+            POOL_SEED.as_bytes(),
+            token_a.as_ref(),
+            token_b.as_ref(),
+            &[CURVE_CONSTANT_PRODUCT],
+        ],
         &program_id,
     );
 
-    let vault_a = Pubkey::new_from_array([0x05; 32]);
-    let mut vault_a_account = Account::new(
-        mollusk
-            .sysvars
-            .rent
-            .minimum_balance(spl_token::state::Account::LEN),
-        spl_token::state::Account::LEN,
-        &token_program,
+    let (vault_a, vault_a_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_a.as_ref()],
+        &program_id,
     );
-    solana_sdk::program_pack::Pack::pack(
-        spl_token::state::Account {
-            mint: token_a,
-            owner: pool_pda,
-            amount: 0,
-            delegate: COption::None,
-            state: spl_token::state::AccountState::Initialized,
-            is_native: COption::None,
-            delegated_amount: 0,
-            close_authority: COption::None,
-        },
-        vault_a_account.data_as_mut_slice(),
-    )
-    .unwrap();
-
-    let vault_b = Pubkey::new_from_array([0x06; 32]);
-    let mut vault_b_account = Account::new(
-        mollusk
-            .sysvars
-            .rent
-            .minimum_balance(spl_token::state::Account::LEN),
-        spl_token::state::Account::LEN,
-        &token_program,
+    let (vault_b, vault_b_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_b.as_ref()],
+        &program_id,
     );
-    solana_sdk::program_pack::Pack::pack(
-        spl_token::state::Account {
-            mint: token_b,
-            owner: pool_pda,
-            amount: 0,
-            delegate: COption::None,
-            state: spl_token::state::AccountState::Initialized,
-            is_native: COption::None,
-            delegated_amount: 0,
-            close_authority: COption::None,
-        },
-        vault_b_account.data_as_mut_slice(),
-    )
-    .unwrap();
 
     let (lp_mint, lp_mint_bump) =
         Pubkey::find_program_address(&[LP_MINT_SEED.as_bytes(), pool_pda.as_ref()], &program_id);
+    let (lp_vault, lp_vault_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), lp_mint.as_ref()],
+        &program_id,
+    );
+
+    let (config, config_account) = config_account(&mollusk, &program_id, Pubkey::new_unique());
 
     let fee_rate: u16 = 30;
+    let flash_loan_fee_rate: u16 = 9;
+    let lp_decimals: u8 = 9;
+    let curve_type = CURVE_CONSTANT_PRODUCT;
 
     let mut data = vec![0u8];
     data.extend_from_slice(&fee_rate.to_le_bytes());
+    data.extend_from_slice(&flash_loan_fee_rate.to_le_bytes());
     data.push(pool_bump);
     data.push(lp_mint_bump);
+    data.push(vault_a_bump);
+    data.push(vault_b_bump);
+    data.push(lp_vault_bump);
+    data.push(lp_decimals);
+    data.push(curve_type);
+    data.extend_from_slice(&0u64.to_le_bytes());
 
     let ix = Instruction {
         program_id,
@@ -149,6 +141,10 @@ fn test_initialize_success() {
             AccountMeta::new(vault_b, false),
             AccountMeta::new_readonly(system_program, false),
             AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new(lp_vault, false),
+            AccountMeta::new_readonly(config, false),
         ],
         data,
     };
@@ -156,6 +152,9 @@ fn test_initialize_success() {
     let authority_account = Account::new(1_000_000_000, 0, &system_program);
     let pool_account = Account::new(0, 0, &system_program);
     let lp_mint_account = Account::new(0, 0, &system_program);
+    let vault_a_account = Account::new(0, 0, &system_program);
+    let vault_b_account = Account::new(0, 0, &system_program);
+    let lp_vault_account = Account::new(0, 0, &system_program);
 
     let result = mollusk.process_and_validate_instruction(
         &ix,
@@ -169,9 +168,362 @@ fn test_initialize_success() {
             (vault_b, vault_b_account),
             (system_program, system_account),
             (token_program, token_account),
+            (lp_vault, lp_vault_account),
+            (config, config_account),
         ],
         &[mollusk_svm::result::Check::success()],
     );
 
     assert!(!result.program_result.is_err());
+
+    let pool_state =
+        bytemuck::pod_read_unaligned::<Pool>(&result.get_account(&pool_pda).unwrap().data);
+    assert_eq!(pool_state.authority, authority.to_bytes());
+    assert_eq!(pool_state.protocol_fee_rate, PROTOCOL_FEE_RATE);
+    assert_eq!(pool_state.flash_loan_fee_rate, flash_loan_fee_rate);
+    assert_eq!(pool_state.vault_a, vault_a.to_bytes());
+    assert_eq!(pool_state.vault_a_bump, vault_a_bump);
+    assert_eq!(pool_state.vault_b_bump, vault_b_bump);
+    assert_eq!(pool_state.lp_vault_bump, lp_vault_bump);
+    assert_eq!(pool_state.curve_type, curve_type);
+
+    let lp_mint_state =
+        spl_token::state::Mint::unpack(&result.get_account(&lp_mint).unwrap().data).unwrap();
+    assert_eq!(lp_mint_state.decimals, lp_decimals);
+
+    // The vaults are created empty, owned by the pool, with no delegate or
+    // close authority.
+    for (vault, mint) in [(vault_a, token_a), (vault_b, token_b), (lp_vault, lp_mint)] {
+        let vault_account = result.get_account(&vault).unwrap();
+        assert_eq!(vault_account.owner, token_program);
+
+        let vault_state = spl_token::state::Account::unpack(&vault_account.data).unwrap();
+        assert_eq!(vault_state.mint, mint);
+        assert_eq!(vault_state.owner, pool_pda);
+        assert_eq!(vault_state.amount, 0);
+        assert_eq!(vault_state.delegate, COption::None);
+        assert_eq!(vault_state.close_authority, COption::None);
+    }
+}
+
+fn initialize_with_fees(fee_rate: u16, flash_loan_fee_rate: u16, check: Check) {
+    initialize_pool(
+        fee_rate,
+        flash_loan_fee_rate,
+        spl_token::ID,
+        &[],
+        false,
+        6,
+        6,
+        CURVE_CONSTANT_PRODUCT,
+        0,
+        check,
+    );
+}
+
+/// Initializes a pool whose token B is owned by `token_program_b`, with
+/// `extensions` (Token-2022 TLV entries) appended to the mint.
+fn initialize_with_mint_b(
+    fee_rate: u16,
+    token_program_b: Pubkey,
+    extensions: &[u8],
+    check: Check,
+) -> Pool {
+    initialize_pool(
+        fee_rate,
+        fee_rate,
+        token_program_b,
+        extensions,
+        false,
+        6,
+        6,
+        CURVE_CONSTANT_PRODUCT,
+        0,
+        check,
+    )
+}
+
+/// Like `initialize_with_mint_b`, optionally passing the vaults in each
+/// other's place.
+#[allow(clippy::too_many_arguments)]
+fn initialize_pool(
+    fee_rate: u16,
+    flash_loan_fee_rate: u16,
+    token_program_b: Pubkey,
+    extensions: &[u8],
+    swap_vaults: bool,
+    lp_decimals: u8,
+    mint_b_decimals: u8,
+    curve_type: u8,
+    amp: u64,
+    check: Check,
+) -> Pool {
+    let program_id = Pubkey::new_unique();
+    let mut mollusk = Mollusk::new(&program_id, "tests/elfs/amm_pinocchio");
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    mollusk.add_program(&spl_token::ID, "tests/elfs/spl_token-3.5.0");
+    mollusk_svm_programs_token::token2022::add_program(&mut mollusk);
+
+    let authority = Pubkey::new_unique();
+    let token_a = Pubkey::new_from_array([0x03; 32]);
+    let token_b = Pubkey::new_from_array([0x02; 32]);
+
+    let (pool_pda, pool_bump) = Pubkey::find_program_address(
+        &[
+            POOL_SEED.as_bytes(),
+            token_a.as_ref(),
+            token_b.as_ref(),
+            &[curve_type],
+        ],
+        &program_id,
+    );
+    let (lp_mint, lp_mint_bump) =
+        Pubkey::find_program_address(&[LP_MINT_SEED.as_bytes(), pool_pda.as_ref()], &program_id);
+    let (mut vault_a, vault_a_bump) = vault_address(&program_id, &pool_pda, &token_a);
+    let (mut vault_b, vault_b_bump) = vault_address(&program_id, &pool_pda, &token_b);
+    let (lp_vault, lp_vault_bump) = vault_address(&program_id, &pool_pda, &lp_mint);
+    if swap_vaults {
+        (vault_a, vault_b) = (vault_b, vault_a);
+    }
+    let (config, config_state) = config_account(&mollusk, &program_id, Pubkey::new_unique());
+
+    let mut data = vec![0u8];
+    data.extend_from_slice(&fee_rate.to_le_bytes());
+    data.extend_from_slice(&flash_loan_fee_rate.to_le_bytes());
+    data.push(pool_bump);
+    data.push(lp_mint_bump);
+    data.push(vault_a_bump);
+    data.push(vault_b_bump);
+    data.push(lp_vault_bump);
+    data.push(lp_decimals);
+    data.push(curve_type);
+    data.extend_from_slice(&amp.to_le_bytes());
+
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(authority, true),
+            AccountMeta::new(pool_pda, false),
+            AccountMeta::new(token_a, false),
+            AccountMeta::new(token_b, false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new(vault_a, false),
+            AccountMeta::new(vault_b, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(token_program_b, false),
+            AccountMeta::new(lp_vault, false),
+            AccountMeta::new_readonly(config, false),
+        ],
+        data,
+    };
+
+    let mut mint_b = with_owner(mint_account(&mollusk, None, 200_000_000), token_program_b);
+    let mut mint_b_state = Mint::unpack(&mint_b.data).unwrap();
+    mint_b_state.decimals = mint_b_decimals;
+    Mint::pack(mint_b_state, &mut mint_b.data).unwrap();
+    if !extensions.is_empty() {
+        mint_b.data.resize(spl_token::state::Account::LEN, 0);
+        mint_b.data.push(1);
+        mint_b.data.extend_from_slice(extensions);
+    }
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &[
+            (authority, Account::new(1_000_000_000, 0, &system_program)),
+            (pool_pda, Account::new(0, 0, &system_program)),
+            (token_a, mint_account(&mollusk, None, 100_000_000)),
+            (token_b, mint_b),
+            (lp_mint, Account::new(0, 0, &system_program)),
+            (vault_a, Account::new(0, 0, &system_program)),
+            (vault_b, Account::new(0, 0, &system_program)),
+            (lp_vault, Account::new(0, 0, &system_program)),
+            (config, config_state),
+            (system_program, system_account),
+            (
+                spl_token::ID,
+                program::create_program_account_loader_v3(&spl_token::ID),
+            ),
+            (
+                TOKEN_2022_ID,
+                program::create_program_account_loader_v3(&TOKEN_2022_ID),
+            ),
+        ],
+        &[check],
+    );
+
+    result
+        .get_account(&pool_pda)
+        .map(|account| bytemuck::pod_read_unaligned::<Pool>(&account.data))
+        .unwrap_or_else(Pool::zeroed)
+}
+
+#[test]
+fn test_initialize_invalid_vault_address() {
+    initialize_pool(
+        30,
+        30,
+        spl_token::ID,
+        &[],
+        true,
+        6,
+        6,
+        CURVE_CONSTANT_PRODUCT,
+        0,
+        Check::err(amm_error(AmmError::InvalidVaultAddress)),
+    );
+}
+
+#[test]
+fn test_initialize_invalid_lp_decimals() {
+    initialize_pool(
+        30,
+        30,
+        spl_token::ID,
+        &[],
+        false,
+        MAX_LP_DECIMALS + 1,
+        6,
+        CURVE_CONSTANT_PRODUCT,
+        0,
+        Check::err(amm_error(AmmError::InvalidLpDecimals)),
+    );
+}
+
+#[test]
+fn test_initialize_invalid_curve_type() {
+    initialize_pool(
+        30,
+        30,
+        spl_token::ID,
+        &[],
+        false,
+        6,
+        6,
+        u8::MAX,
+        0,
+        Check::err(amm_error(AmmError::InvalidCurveType)),
+    );
+}
+
+#[test]
+fn test_initialize_stable_swap() {
+    let pool_state = initialize_pool(
+        30,
+        30,
+        spl_token::ID,
+        &[],
+        false,
+        6,
+        6,
+        CURVE_STABLE_SWAP,
+        100,
+        Check::success(),
+    );
+
+    assert_eq!(pool_state.curve_type, CURVE_STABLE_SWAP);
+    assert_eq!(pool_state.initial_amp, 100);
+    assert_eq!(pool_state.target_amp, 100);
+    assert_eq!(pool_state.amp(pool_state.ramp_stop_timestamp), 100);
+}
+
+#[test]
+fn test_initialize_stable_swap_mismatched_decimals() {
+    initialize_pool(
+        30,
+        30,
+        spl_token::ID,
+        &[],
+        false,
+        6,
+        9,
+        CURVE_STABLE_SWAP,
+        100,
+        Check::err(amm_error(AmmError::MismatchedDecimals)),
+    );
+
+    // Constant product prices by the reserves, so decimals may differ.
+    initialize_pool(
+        30,
+        30,
+        spl_token::ID,
+        &[],
+        false,
+        6,
+        9,
+        CURVE_CONSTANT_PRODUCT,
+        0,
+        Check::success(),
+    );
+}
+
+#[test]
+fn test_initialize_invalid_amp() {
+    for (curve_type, amp) in [
+        (CURVE_STABLE_SWAP, 0),
+        (CURVE_STABLE_SWAP, MAX_AMP + 1),
+        (CURVE_CONSTANT_PRODUCT, 100),
+    ] {
+        initialize_pool(
+            30,
+            30,
+            spl_token::ID,
+            &[],
+            false,
+            6,
+            6,
+            curve_type,
+            amp,
+            Check::err(amm_error(AmmError::InvalidAmp)),
+        );
+    }
+}
+
+#[test]
+fn test_initialize_invalid_fee_rate() {
+    initialize_with_fees(10_001, 30, Check::err(amm_error(AmmError::InvalidFeeRate)));
+    initialize_with_fees(30, 10_001, Check::err(amm_error(AmmError::InvalidFeeRate)));
+}
+
+#[test]
+fn test_initialize_token_2022_mint() {
+    let pool_state = initialize_with_mint_b(30, TOKEN_2022_ID, &[], Check::success());
+
+    assert_eq!(pool_state.token_program_a, spl_token::ID.to_bytes());
+    assert_eq!(pool_state.token_program_b, TOKEN_2022_ID.to_bytes());
+}
+
+#[test]
+fn test_initialize_transfer_hook_mint() {
+    // TransferHook: type 14, authority and hook program.
+    let mut transfer_hook = vec![14, 0, 64, 0];
+    transfer_hook.extend_from_slice(&[0; 32]);
+    transfer_hook.extend_from_slice(Pubkey::new_unique().as_ref());
+
+    let pool = initialize_with_mint_b(30, TOKEN_2022_ID, &transfer_hook, Check::success());
+
+    assert_eq!(pool.token_program_b, TOKEN_2022_ID.to_bytes());
+}
+
+#[test]
+fn test_initialize_rejects_unsupported_mint_extensions() {
+    // NonTransferable: type 9, no data.
+    initialize_with_mint_b(
+        30,
+        TOKEN_2022_ID,
+        &[9, 0, 0, 0],
+        Check::err(amm_error(AmmError::UnsupportedMintExtension)),
+    );
+
+    // PermanentDelegate: type 12, 32-byte delegate.
+    let mut permanent_delegate = vec![12, 0, 32, 0];
+    permanent_delegate.extend_from_slice(Pubkey::new_unique().as_ref());
+    initialize_with_mint_b(
+        30,
+        TOKEN_2022_ID,
+        &permanent_delegate,
+        Check::err(amm_error(AmmError::UnsupportedMintExtension)),
+    );
 }
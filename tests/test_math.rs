@@ -0,0 +1,221 @@
+use amm_pinocchio::{
+    helper::{calculate_swap_input, calculate_swap_output},
+    math::{integer_sqrt, mul_add_div_wide, mul_div_ceil, mul_div_floor, mul_div_wide},
+};
+use pinocchio::program_error::ProgramError;
+
+#[test]
+fn test_mul_div_rounding() {
+    assert_eq!(mul_div_floor(7, 3, 2), Ok(10));
+    assert_eq!(mul_div_ceil(7, 3, 2), Ok(11));
+    assert_eq!(mul_div_floor(6, 3, 2), Ok(9));
+    assert_eq!(mul_div_ceil(6, 3, 2), Ok(9));
+    assert_eq!(mul_div_floor(1, 1, 3), Ok(0));
+    assert_eq!(mul_div_ceil(1, 1, 3), Ok(1));
+}
+
+#[test]
+fn test_mul_div_wide_intermediate() {
+    // The product needs 128 bits, the result fits in a `u64`.
+    assert_eq!(mul_div_floor(u64::MAX, u64::MAX, u64::MAX), Ok(u64::MAX));
+    assert_eq!(
+        mul_div_ceil(u64::MAX, u64::MAX - 1, u64::MAX),
+        Ok(u64::MAX - 1)
+    );
+    assert_eq!(mul_div_floor(u64::MAX, 3, 4), Ok(u64::MAX / 4 * 3 + 2));
+}
+
+#[test]
+fn test_mul_div_overflow() {
+    assert_eq!(
+        mul_div_floor(u64::MAX, 2, 1),
+        Err(ProgramError::ArithmeticOverflow)
+    );
+    assert_eq!(
+        mul_div_ceil(u64::MAX, u64::MAX, u64::MAX - 1),
+        Err(ProgramError::ArithmeticOverflow)
+    );
+    assert_eq!(
+        mul_div_floor(1, 1, 0),
+        Err(ProgramError::ArithmeticOverflow)
+    );
+    assert_eq!(mul_div_ceil(1, 1, 0), Err(ProgramError::ArithmeticOverflow));
+}
+
+#[test]
+fn test_mul_div_wide() {
+    assert_eq!(mul_div_wide(7, 3, 2), Ok(10));
+    assert_eq!(mul_div_wide(u128::MAX, u128::MAX, u128::MAX), Ok(u128::MAX));
+    assert_eq!(mul_div_wide(u128::MAX, 3, 4), Ok(u128::MAX / 4 * 3 + 2));
+    assert_eq!(
+        mul_div_wide(1 << 64, 1 << 64, (1 << 64) + 1),
+        Ok(u64::MAX as u128)
+    );
+
+    // 256-bit products, checked against arbitrary-precision arithmetic.
+    assert_eq!(
+        mul_div_wide(
+            272996653310673477252411125948039410165,
+            40125655066622386354123033417875897284,
+            160696727714092153506162855508696159611,
+        ),
+        Ok(68166724368997616696999234131345098657)
+    );
+    assert_eq!(
+        mul_div_wide(
+            71443919313006467319876364715437247515,
+            303980036011023439451899638628793921164,
+            206700438978357920216854609496785597363,
+        ),
+        Ok(105067629623226107157972704436926939792)
+    );
+    assert_eq!(
+        mul_div_wide(
+            7595820948603237271392428817836667705,
+            3132278396590092758918285954981797561,
+            233598352214827113536248690594353517643,
+        ),
+        Ok(101851000386321342698865981348061960)
+    );
+}
+
+#[test]
+fn test_mul_div_wide_is_exact() {
+    let mut value = 0x9E37_79B9_7F4A_7C15_F39C_C060_5CED_C835_u128;
+    for _ in 0..1_000 {
+        value = value.rotate_left(17) ^ value.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        let (a, b) = (value, value.rotate_left(64) | 1);
+
+        assert_eq!(mul_div_wide(a, b, b), Ok(a));
+        assert_eq!(mul_div_wide(a, b, a.max(1)), Ok(if a == 0 { 0 } else { b }));
+    }
+}
+
+#[test]
+fn test_mul_div_wide_overflow() {
+    assert_eq!(
+        mul_div_wide(u128::MAX, 2, 1),
+        Err(ProgramError::ArithmeticOverflow)
+    );
+    assert_eq!(
+        mul_div_wide(u128::MAX, u128::MAX, u128::MAX - 1),
+        Err(ProgramError::ArithmeticOverflow)
+    );
+    assert_eq!(mul_div_wide(1, 1, 0), Err(ProgramError::ArithmeticOverflow));
+}
+
+#[test]
+fn test_swap_output_with_9_decimal_reserves() {
+    let reserve = 1_000_000_000_000_000_000;
+
+    let (amount_out, fee) = calculate_swap_output(1_000_000_000_000, reserve, reserve, 30).unwrap();
+
+    // 0.3% fee, then a slight price impact, rounded down.
+    assert_eq!(fee, 3_000_000_000);
+    assert_eq!(amount_out, 996_999_005_991);
+}
+
+#[test]
+fn test_swap_input_with_9_decimal_reserves() {
+    let (reserve_in, reserve_out) = (1_000_000_000_000_000_000, 3_000_000_000_000_000_000);
+
+    for amount_out in (1..reserve_out / 2).step_by((reserve_out / 97) as usize) {
+        let (amount_in, _) = calculate_swap_input(amount_out, reserve_in, reserve_out, 30).unwrap();
+
+        let (paid, _) = calculate_swap_output(amount_in, reserve_in, reserve_out, 30).unwrap();
+        assert!(paid >= amount_out);
+
+        let (short, _) = calculate_swap_output(amount_in - 1, reserve_in, reserve_out, 30).unwrap();
+        assert!(short < amount_out);
+    }
+}
+
+/// Checks that `root` is the floor of the square root of `n`.
+fn assert_floor_sqrt(n: u128, root: u128) {
+    assert!(root * root <= n, "sqrt({n}) = {root} is too large");
+    assert!(
+        (root + 1).checked_mul(root + 1).is_none_or(|next| next > n),
+        "sqrt({n}) = {root} is too small"
+    );
+}
+
+#[test]
+fn test_integer_sqrt_small_values() {
+    assert_eq!(integer_sqrt(0), 0);
+    assert_eq!(integer_sqrt(1), 1);
+    assert_eq!(integer_sqrt(2), 1);
+    assert_eq!(integer_sqrt(3), 1);
+    assert_eq!(integer_sqrt(4), 2);
+
+    for n in 0..=1 << 20 {
+        assert_floor_sqrt(n, integer_sqrt(n));
+    }
+}
+
+#[test]
+fn test_integer_sqrt_edges() {
+    assert_eq!(integer_sqrt(u128::MAX), u64::MAX as u128);
+    assert_eq!(
+        integer_sqrt(u64::MAX as u128 * u64::MAX as u128),
+        u64::MAX as u128
+    );
+
+    // Powers of two and their neighbours.
+    for shift in 0..128 {
+        let power = 1_u128 << shift;
+        for n in [power - 1, power, power + 1] {
+            assert_floor_sqrt(n, integer_sqrt(n));
+        }
+    }
+
+    // Perfect squares and their neighbours, across the whole range.
+    for shift in 0..64 {
+        for root in [
+            (1_u128 << shift) - 1,
+            1 << shift,
+            (1 << shift) + 1,
+            3 << shift >> 1,
+        ] {
+            if root > u64::MAX as u128 {
+                continue;
+            }
+            let square = root * root;
+            assert_eq!(integer_sqrt(square), root);
+            if square > 0 {
+                assert_eq!(integer_sqrt(square - 1), root - 1);
+            }
+            if let Some(next) = square.checked_add(1) {
+                assert_floor_sqrt(next, integer_sqrt(next));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_integer_sqrt_large_products() {
+    // 5,000 tokens with 9 decimals on each side of a first deposit.
+    let amount = 5_000_000_000_000_u128;
+    assert_eq!(integer_sqrt(amount * amount), amount);
+
+    let mut n = 0x9e37_79b9_7f4a_7c15_f39c_c060_5ced_c834_u128;
+    for _ in 0..100_000 {
+        n = n.rotate_left(17) ^ n.wrapping_mul(0x2545_f491_4f6c_dd1d);
+        assert_floor_sqrt(n, integer_sqrt(n));
+        assert_floor_sqrt(n >> 64, integer_sqrt(n >> 64));
+    }
+}
+
+#[test]
+fn test_mul_add_div_wide() {
+    assert_eq!(mul_add_div_wide(7, 3, 1, 2), Ok(11));
+    // The addend carries into the high half of the product.
+    assert_eq!(mul_add_div_wide(u128::MAX, 1, 1, 1 << 64), Ok(1 << 64));
+    assert_eq!(
+        mul_add_div_wide(1 << 64, 1 << 64, u128::MAX, 1 << 65),
+        Ok((1 << 63) + (u128::MAX >> 65))
+    );
+    assert_eq!(
+        mul_add_div_wide(u128::MAX, u128::MAX, u128::MAX, u128::MAX),
+        Err(ProgramError::ArithmeticOverflow)
+    );
+}
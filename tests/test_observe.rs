@@ -0,0 +1,133 @@
+mod common;
+
+use amm_pinocchio::{
+    curve::{CURVE_STABLE_SWAP, StableSwap, SwapCurve},
+    states::{Observation, Pool},
+};
+use bytemuck::Zeroable;
+use common::{TestPool, ix_data};
+use mollusk_svm::result::Check;
+use solana_sdk::instruction::AccountMeta;
+
+#[test]
+fn test_observe_success() {
+    let (mut mollusk, mut pool) = TestPool::new(100_000, 200_000, 100_000);
+    pool.state.last_update_timestamp = 1_000;
+    mollusk.sysvars.clock.unix_timestamp = 1_010;
+
+    let ix = pool.instruction(vec![8], vec![AccountMeta::new_readonly(pool.pool, false)]);
+
+    // Token A traded at 2 B and token B at 0.5 A for 10 seconds.
+    let observation = Observation {
+        timestamp: 1_010,
+        price_a_cumulative: ((2u128 << 64) * 10).to_le_bytes(),
+        price_b_cumulative: ((1u128 << 63) * 10).to_le_bytes(),
+    };
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[
+            Check::success(),
+            Check::return_data(bytemuck::bytes_of(&observation)),
+        ],
+    );
+}
+
+#[test]
+fn test_swap_updates_oracle() {
+    let (mut mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.last_update_timestamp = 1_000;
+    mollusk.sysvars.clock.unix_timestamp = 1_005;
+
+    let ix = pool.instruction(ix_data(2, &[10_000, 9_000]), pool.swap_accounts(true));
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    // The pre-swap 1:1 price is what held for the 5 elapsed seconds.
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.last_update_timestamp, 1_005);
+    assert_eq!(
+        u128::from_le_bytes(pool_state.price_a_cumulative),
+        (1u128 << 64) * 5
+    );
+}
+
+#[test]
+fn test_twap_between_observations() {
+    let mut pool = Pool {
+        reserve_a: 100_000,
+        reserve_b: 400_000,
+        ..Pool::zeroed()
+    };
+
+    pool.update_oracle(100).unwrap();
+    let start = pool.observe(100).unwrap();
+
+    // Price of A is 4 B for 30 seconds, then 1 B for 10 seconds.
+    pool.update_oracle(130).unwrap();
+    pool.reserve_b = 100_000;
+    let end = pool.observe(140).unwrap();
+
+    let (price_a, price_b) = end.twap_since(&start).unwrap();
+    assert_eq!(price_a, (4u128 << 64) * 30 / 40 + (1u128 << 64) * 10 / 40);
+    assert_eq!(price_b, ((1u128 << 62) * 30 + (1u128 << 64) * 10) / 40);
+
+    assert_eq!(start.twap_since(&start), None);
+}
+
+#[test]
+fn test_stable_swap_twap() {
+    let mut pool = Pool {
+        reserve_a: 600_000,
+        reserve_b: 400_000,
+        curve_type: CURVE_STABLE_SWAP,
+        initial_amp: 100,
+        target_amp: 100,
+        ..Pool::zeroed()
+    };
+
+    pool.update_oracle(100).unwrap();
+    let start = pool.observe(100).unwrap();
+    let end = pool.observe(110).unwrap();
+    let (price_a, price_b) = end.twap_since(&start).unwrap();
+
+    // The curve's marginal price, not the 2:3 reserve ratio.
+    let curve = StableSwap { amp: 100 };
+    assert_eq!(price_a, curve.spot_price(600_000, 400_000).unwrap());
+    assert_eq!(price_b, curve.spot_price(400_000, 600_000).unwrap());
+
+    // Matches what a small trade against the pool pays.
+    let (amount_out, _) = curve.swap_out(1_000, 600_000, 400_000, 0).unwrap();
+    assert_eq!(((price_a * 1_000) >> 64) as u64, amount_out);
+    assert!(price_a > (99 << 64) / 100);
+}
+
+#[test]
+fn test_observe_stable_swap() {
+    let (mut mollusk, mut pool) = TestPool::stable_swap(600_000, 400_000, 500_000, 100);
+    pool.state.last_update_timestamp = 1_000;
+    mollusk.sysvars.clock.unix_timestamp = 1_010;
+
+    let ix = pool.instruction(vec![8], vec![AccountMeta::new_readonly(pool.pool, false)]);
+
+    let curve = StableSwap { amp: 100 };
+    let observation = Observation {
+        timestamp: 1_010,
+        price_a_cumulative: (curve.spot_price(600_000, 400_000).unwrap() * 10).to_le_bytes(),
+        price_b_cumulative: (curve.spot_price(400_000, 600_000).unwrap() * 10).to_le_bytes(),
+    };
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[
+            Check::success(),
+            Check::return_data(bytemuck::bytes_of(&observation)),
+        ],
+    );
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use amm_pinocchio::{
+    constants::{BPF_LOADER_UPGRADEABLE_ID, CONFIG_SEED},
+    error::AmmError,
+    states::ProtocolConfig,
+};
+use common::{PROTOCOL_FEE_RATE, amm_error, config_account};
+use mollusk_svm::{Mollusk, program, result::Check};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+const LOADER: Pubkey = Pubkey::new_from_array(BPF_LOADER_UPGRADEABLE_ID);
+
+/// The program under test and its `ProgramData` account, recording
+/// `upgrade_authority`.
+fn setup(upgrade_authority: Pubkey) -> (Mollusk, Pubkey, (Pubkey, Account)) {
+    let program_id = Pubkey::new_unique();
+    let mollusk = Mollusk::new(&program_id, "tests/elfs/amm_pinocchio");
+
+    let (program_data, _) = Pubkey::find_program_address(&[program_id.as_ref()], &LOADER);
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(upgrade_authority.as_ref());
+
+    let mut account = Account::new(1_000_000_000, data.len(), &LOADER);
+    account.data = data;
+
+    (mollusk, program_id, (program_data, account))
+}
+
+/// Runs `InitializeConfig` for `protocol_authority` signed by `signer`,
+/// returning the config account's state, if any, and the config PDA bump.
+fn initialize_config(
+    signer: Pubkey,
+    upgrade_authority: Pubkey,
+    protocol_authority: Pubkey,
+    config_exists: bool,
+    check: Check,
+) -> (Option<ProtocolConfig>, u8) {
+    let (mollusk, program_id, (program_data, program_data_account)) = setup(upgrade_authority);
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (config, config_bump) =
+        Pubkey::find_program_address(&[CONFIG_SEED.as_bytes()], &program_id);
+
+    let mut data = vec![21];
+    data.extend_from_slice(protocol_authority.as_ref());
+    data.extend_from_slice(&PROTOCOL_FEE_RATE.to_le_bytes());
+    data.push(config_bump);
+
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(signer, true),
+            AccountMeta::new(config, false),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new_readonly(system_program, false),
+        ],
+        data,
+    };
+
+    let config_account = if config_exists {
+        config_account(&mollusk, &program_id, Pubkey::new_unique()).1
+    } else {
+        Account::new(0, 0, &system_program)
+    };
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &[
+            (signer, Account::new(1_000_000_000, 0, &system_program)),
+            (config, config_account),
+            (program_data, program_data_account),
+            (system_program, system_account),
+        ],
+        &[check],
+    );
+
+    let config_state = result
+        .get_account(&config)
+        .filter(|account| account.data.len() == ProtocolConfig::LEN)
+        .map(|account| bytemuck::pod_read_unaligned::<ProtocolConfig>(&account.data));
+
+    (config_state, config_bump)
+}
+
+#[test]
+fn test_initialize_config_success() {
+    let upgrade_authority = Pubkey::new_unique();
+    let protocol_authority = Pubkey::new_unique();
+
+    let (config_state, config_bump) = initialize_config(
+        upgrade_authority,
+        upgrade_authority,
+        protocol_authority,
+        false,
+        Check::success(),
+    );
+
+    let config_state = config_state.unwrap();
+    assert_eq!(config_state.authority, protocol_authority.to_bytes());
+    assert_eq!(config_state.protocol_fee_rate, PROTOCOL_FEE_RATE);
+    assert_eq!(config_state.bump, config_bump);
+}
+
+#[test]
+fn test_initialize_config_not_upgrade_authority() {
+    initialize_config(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        false,
+        Check::err(amm_error(AmmError::InvalidAuthority)),
+    );
+}
+
+#[test]
+fn test_initialize_config_twice() {
+    let upgrade_authority = Pubkey::new_unique();
+
+    initialize_config(
+        upgrade_authority,
+        upgrade_authority,
+        Pubkey::new_unique(),
+        true,
+        Check::err(ProgramError::AccountAlreadyInitialized),
+    );
+}
+
+#[test]
+fn test_update_config() {
+    let (mollusk, program_id, _) = setup(Pubkey::new_unique());
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let authority = Pubkey::new_unique();
+    let new_authority = Pubkey::new_unique();
+    let (config, config_state) = config_account(&mollusk, &program_id, authority);
+
+    let update = |signer: Pubkey| Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(signer, true),
+            AccountMeta::new(config, false),
+        ],
+        data: [&[22][..], new_authority.as_ref(), &2_000u16.to_le_bytes()].concat(),
+    };
+    let accounts = [
+        (authority, Account::new(1_000_000_000, 0, &system_program)),
+        (
+            new_authority,
+            Account::new(1_000_000_000, 0, &system_program),
+        ),
+        (config, config_state),
+    ];
+
+    // Only the current protocol authority can hand it over.
+    mollusk.process_and_validate_instruction(
+        &update(new_authority),
+        &accounts,
+        &[Check::err(amm_error(AmmError::InvalidAuthority))],
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &update(authority),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let config_state =
+        bytemuck::pod_read_unaligned::<ProtocolConfig>(&result.get_account(&config).unwrap().data);
+    assert_eq!(config_state.authority, new_authority.to_bytes());
+    assert_eq!(config_state.protocol_fee_rate, 2_000);
+}
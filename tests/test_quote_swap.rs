@@ -0,0 +1,55 @@
+mod common;
+
+use amm_pinocchio::{error::AmmError, instructions::quote_swap::SwapQuote};
+use common::{TestPool, amm_error};
+use mollusk_svm::result::Check;
+use solana_sdk::instruction::AccountMeta;
+
+fn quote_data(amount_in: u64, a_to_b: bool) -> Vec<u8> {
+    let mut data = vec![4u8];
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.push(a_to_b as u8);
+    data
+}
+
+#[test]
+fn test_quote_swap_success() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let ix = pool.instruction(
+        quote_data(10_000, true),
+        vec![AccountMeta::new_readonly(pool.pool, false)],
+    );
+
+    let quote = SwapQuote {
+        amount_out: 9_066,
+        fee: 30,
+        reserve_a: 110_000,
+        reserve_b: 90_934,
+    };
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[
+            Check::success(),
+            Check::return_data(bytemuck::bytes_of(&quote)),
+        ],
+    );
+}
+
+#[test]
+fn test_quote_swap_empty_pool() {
+    let (mollusk, pool) = TestPool::new(0, 0, 0);
+
+    let ix = pool.instruction(
+        quote_data(10_000, false),
+        vec![AccountMeta::new_readonly(pool.pool, false)],
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::EmptyPool))],
+    );
+}
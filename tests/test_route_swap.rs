@@ -0,0 +1,154 @@
+mod common;
+
+use amm_pinocchio::{
+    constants::POOL_SEED, curve::CURVE_CONSTANT_PRODUCT, error::AmmError, states::Pool,
+};
+use common::{
+    TestPool, amm_error, ix_data, mint_account, pool_account, token_account, token_amount,
+    vault_address,
+};
+use mollusk_svm::{Mollusk, result::Check};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+/// An A/B pool plus a B/C pool, with the user holding A and an empty C account.
+fn setup() -> (
+    Mollusk,
+    TestPool,
+    Pubkey,
+    Vec<(Pubkey, Account)>,
+    Vec<AccountMeta>,
+) {
+    let (mollusk, pool_ab) = TestPool::new(100_000, 100_000, 100_000);
+
+    let token_c = Pubkey::new_from_array([0x04; 32]);
+    let (pool_bc, bump) = Pubkey::find_program_address(
+        &[
+            POOL_SEED.as_bytes(),
+            pool_ab.token_b.as_ref(),
+            token_c.as_ref(),
+            &[CURVE_CONSTANT_PRODUCT],
+        ],
+        &pool_ab.program_id,
+    );
+    let (vault_bc_b, vault_bc_b_bump) =
+        vault_address(&pool_ab.program_id, &pool_bc, &pool_ab.token_b);
+    let (vault_bc_c, vault_bc_c_bump) = vault_address(&pool_ab.program_id, &pool_bc, &token_c);
+    let user_token_c = Pubkey::new_from_array([0x0D; 32]);
+
+    let pool_bc_state = Pool {
+        token_a: pool_ab.token_b.to_bytes(),
+        token_b: token_c.to_bytes(),
+        vault_a: vault_bc_b.to_bytes(),
+        vault_b: vault_bc_c.to_bytes(),
+        reserve_a: 100_000,
+        reserve_b: 100_000,
+        bump,
+        vault_a_bump: vault_bc_b_bump,
+        vault_b_bump: vault_bc_c_bump,
+        ..pool_ab.state
+    };
+
+    let mut accounts = pool_ab.accounts(&mollusk);
+    accounts.extend([
+        (token_c, mint_account(&mollusk, None, 100_000_000)),
+        (
+            pool_bc,
+            pool_account(&mollusk, &pool_ab.program_id, &pool_bc_state),
+        ),
+        (
+            vault_bc_b,
+            token_account(&mollusk, pool_ab.token_b, pool_bc, 100_000),
+        ),
+        (
+            vault_bc_c,
+            token_account(&mollusk, token_c, pool_bc, 100_000),
+        ),
+        (
+            user_token_c,
+            token_account(&mollusk, token_c, pool_ab.user, 0),
+        ),
+    ]);
+
+    let metas = vec![
+        AccountMeta::new(pool_ab.user, true),
+        AccountMeta::new(pool_ab.user_token_a, false),
+        AccountMeta::new(user_token_c, false),
+        AccountMeta::new_readonly(pool_ab.token_a, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new(pool_ab.pool, false),
+        AccountMeta::new(pool_ab.vault_a, false),
+        AccountMeta::new(pool_ab.vault_b, false),
+        AccountMeta::new_readonly(pool_ab.token_b, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new(pool_bc, false),
+        AccountMeta::new(vault_bc_b, false),
+        AccountMeta::new(vault_bc_c, false),
+        AccountMeta::new_readonly(token_c, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+    ];
+
+    (mollusk, pool_ab, user_token_c, accounts, metas)
+}
+
+#[test]
+fn test_route_swap_success() {
+    let (mollusk, pool_ab, user_token_c, accounts, metas) = setup();
+
+    let ix = Instruction {
+        program_id: pool_ab.program_id,
+        accounts: metas,
+        data: ix_data(6, &[10_000, 8_000]),
+    };
+
+    let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+    let user_token_a = result.get_account(&pool_ab.user_token_a).unwrap();
+    let user_token_c = result.get_account(&user_token_c).unwrap();
+    assert_eq!(token_amount(user_token_a), 1_000_000 - 10_000);
+    assert_eq!(token_amount(user_token_c), 8_288);
+
+    let pool_ab_state = TestPool::pool_state(result.get_account(&pool_ab.pool).unwrap());
+    assert_eq!(pool_ab_state.reserve_a, 110_000);
+    assert_eq!(pool_ab_state.reserve_b, 100_000 - 9_066);
+}
+
+#[test]
+fn test_route_swap_slippage_exceeded() {
+    let (mollusk, pool_ab, _, accounts, metas) = setup();
+
+    let ix = Instruction {
+        program_id: pool_ab.program_id,
+        accounts: metas,
+        data: ix_data(6, &[10_000, 8_289]),
+    };
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_error(AmmError::SlippageExceeded))],
+    );
+}
+
+#[test]
+fn test_route_swap_broken_chain() {
+    let (mollusk, pool_ab, _, accounts, mut metas) = setup();
+
+    // The second hop's input vault must hold token B, not token C.
+    metas.swap(11, 12);
+
+    let ix = Instruction {
+        program_id: pool_ab.program_id,
+        accounts: metas,
+        data: ix_data(6, &[10_000, 0]),
+    };
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_error(AmmError::InvalidVault))],
+    );
+}
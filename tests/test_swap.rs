@@ -1,12 +1,24 @@
+mod common;
+
 use amm_pinocchio::{
-    constants::{LP_MINT_SEED, POOL_SEED},
+    constants::{LP_MINT_SEED, POOL_SEED, SWAPS_DISABLED, VAULT_SEED},
+    curve::{CURVE_CONSTANT_PRODUCT, StableSwap, SwapCurve},
+    error::AmmError,
+    helper::calculate_swap_output,
+    instructions::deadline::DEADLINE_SLOT,
     states::Pool,
 };
+use bytemuck::Zeroable;
+use common::{
+    TOKEN_2022_ID, TestPool, amm_error, ix_data, set_account, token_amount, transfer_fee_mint,
+    transfer_fee_token_account,
+};
 
-use mollusk_svm::{Mollusk, program};
+use mollusk_svm::{Mollusk, program, result::Check};
 use solana_sdk::{
     account::{Account, WritableAccount},
     instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
     program_option::COption,
     program_pack::Pack,
     pubkey::Pubkey,
@@ -72,14 +84,22 @@ fn test_swap_success() {
     .unwrap();
 
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
-        &[POOL_SEED.as_bytes(), token_a.as_ref(), token_b.as_ref()],
+        &[
+            POOL_SEED.as_bytes(),
+            token_a.as_ref(),
+            token_b.as_ref(),
+            &[CURVE_CONSTANT_PRODUCT],
+        ],
         &program_id,
     );
 
     let (lp_mint, lp_mint_bump) =
         Pubkey::find_program_address(&[LP_MINT_SEED.as_bytes(), pool_pda.as_ref()], &program_id);
 
-    let vault_a = Pubkey::new_from_array([0x05; 32]);
+    let (vault_a, vault_a_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_a.as_ref()],
+        &program_id,
+    );
     let mut vault_a_acc = Account::new(
         mollusk
             .sysvars
@@ -104,7 +124,10 @@ fn test_swap_success() {
     )
     .unwrap();
 
-    let vault_b = Pubkey::new_from_array([0x09; 32]);
+    let (vault_b, vault_b_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_b.as_ref()],
+        &program_id,
+    );
     let mut vault_b_acc = Account::new(
         mollusk
             .sysvars
@@ -145,7 +168,11 @@ fn test_swap_success() {
         fee_rate: 30,
         bump: pool_bump,
         lp_mint_bump,
-        _padding: [0; 4],
+        vault_a_bump,
+        vault_b_bump,
+        token_program_a: token_program.to_bytes(),
+        token_program_b: token_program.to_bytes(),
+        ..Zeroable::zeroed()
     };
     pool_account
         .data_as_mut_slice()
@@ -241,6 +268,7 @@ fn test_swap_success() {
             AccountMeta::new(user_token_a, false),
             AccountMeta::new(user_token_b, false),
             AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(token_program, false),
         ],
         data,
     };
@@ -263,3 +291,234 @@ fn test_swap_success() {
 
     assert!(!result.program_result.is_err());
 }
+
+#[test]
+fn test_swap_slippage_exceeded() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let ix = pool.instruction(ix_data(2, &[10_000, 9_500]), pool.swap_accounts(true));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::SlippageExceeded))],
+    );
+}
+
+#[test]
+fn test_swap_invalid_vault() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let mut accounts = pool.swap_accounts(true);
+    accounts[4] = AccountMeta::new(pool.vault_b, false);
+    let ix = pool.instruction(ix_data(2, &[10_000, 0]), accounts);
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InvalidVault))],
+    );
+}
+
+#[test]
+fn test_swap_disabled() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.status = SWAPS_DISABLED;
+
+    let ix = pool.instruction(ix_data(2, &[10_000, 0]), pool.swap_accounts(true));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::SwapsDisabled))],
+    );
+}
+
+#[test]
+fn test_swap_empty_pool() {
+    let (mollusk, pool) = TestPool::new(0, 0, 0);
+
+    let ix = pool.instruction(ix_data(2, &[10_000, 0]), pool.swap_accounts(true));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::EmptyPool))],
+    );
+}
+
+#[test]
+fn test_swap_deadline() {
+    let (mut mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+    mollusk.warp_to_slot(100);
+
+    let deadline_data = |slot: u64| {
+        let mut data = ix_data(2, &[10_000, 9_000]);
+        data.push(DEADLINE_SLOT);
+        data.extend_from_slice(&slot.to_le_bytes());
+        data
+    };
+
+    mollusk.process_and_validate_instruction(
+        &pool.instruction(deadline_data(100), pool.swap_accounts(true)),
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    mollusk.process_and_validate_instruction(
+        &pool.instruction(deadline_data(99), pool.swap_accounts(true)),
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::DeadlineExceeded))],
+    );
+}
+
+#[test]
+fn test_swap_accrues_protocol_fee() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.protocol_fee_rate = 5_000;
+
+    let ix = pool.instruction(ix_data(2, &[10_000, 9_000]), pool.swap_accounts(true));
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    // The 30 token fee is split evenly; only the LP half stays in the reserve.
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.protocol_fees_a, 15);
+    assert_eq!(pool_state.reserve_a, 110_000 - 15);
+    assert_eq!(pool_state.reserve_b, 100_000 - 9_066);
+}
+
+#[test]
+fn test_swap_stable_swap() {
+    let (mollusk, pool) = TestPool::stable_swap(100_000, 100_000, 100_000, 100);
+
+    // Above what the constant-product curve pays for the same trade.
+    let ix = pool.instruction(ix_data(2, &[10_000, 9_900]), pool.swap_accounts(true));
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    let (amount_out, _) = StableSwap { amp: 100 }
+        .swap_out(10_000, 100_000, 100_000, 30)
+        .unwrap();
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 110_000);
+    assert_eq!(pool_state.reserve_b, 100_000 - amount_out);
+}
+
+#[test]
+fn test_swap_token_2022_output() {
+    let (mollusk, pool) =
+        TestPool::with_token_programs(100_000, 100_000, 100_000, spl_token::ID, TOKEN_2022_ID);
+
+    let ix = pool.instruction(ix_data(2, &[10_000, 9_000]), pool.swap_accounts(true));
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    let user_token_b = result.get_account(&pool.user_token_b).unwrap();
+    assert_eq!(user_token_b.owner, TOKEN_2022_ID);
+    assert_eq!(token_amount(user_token_b), 1_000_000 + 9_066);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 110_000);
+    assert_eq!(pool_state.reserve_b, 100_000 - 9_066);
+}
+
+#[test]
+fn test_swap_forwards_hook_accounts() {
+    let (mollusk, pool) =
+        TestPool::with_token_programs(100_000, 100_000, 100_000, spl_token::ID, TOKEN_2022_ID);
+
+    // Remaining accounts are passed through to both transfers, where token
+    // programs ignore any that no transfer hook asks for.
+    let mut accounts = pool.swap_accounts(true);
+    accounts.push(AccountMeta::new_readonly(pool.token_a, false));
+    accounts.push(AccountMeta::new_readonly(pool.token_b, false));
+    let ix = pool.instruction(ix_data(2, &[10_000, 9_000]), accounts);
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    let user_token_b = result.get_account(&pool.user_token_b).unwrap();
+    assert_eq!(token_amount(user_token_b), 1_000_000 + 9_066);
+}
+
+#[test]
+fn test_swap_wrong_token_program() {
+    let (mollusk, pool) =
+        TestPool::with_token_programs(100_000, 100_000, 100_000, spl_token::ID, TOKEN_2022_ID);
+
+    let mut accounts = pool.swap_accounts(true);
+    accounts[9] = AccountMeta::new_readonly(spl_token::ID, false);
+    let ix = pool.instruction(ix_data(2, &[10_000, 0]), accounts);
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(ProgramError::IncorrectProgramId)],
+    );
+}
+
+#[test]
+fn test_swap_fee_on_transfer_input() {
+    let (mollusk, pool) =
+        TestPool::with_token_programs(100_000, 100_000, 100_000, TOKEN_2022_ID, spl_token::ID);
+
+    // Token A charges 1% per transfer, so vault A only receives 9_900.
+    let mut accounts = pool.accounts(&mollusk);
+    set_account(
+        &mut accounts,
+        pool.token_a,
+        transfer_fee_mint(&mollusk, 100_000_000, 100),
+    );
+    set_account(
+        &mut accounts,
+        pool.vault_a,
+        transfer_fee_token_account(&mollusk, pool.token_a, pool.pool, 100_000),
+    );
+    set_account(
+        &mut accounts,
+        pool.user_token_a,
+        transfer_fee_token_account(&mollusk, pool.token_a, pool.user, pool.user_balance),
+    );
+
+    let (expected_out, _) = calculate_swap_output(9_900, 100_000, 100_000, 30).unwrap();
+
+    // The slippage limit applies to the output for the received amount.
+    let ix = pool.instruction(
+        ix_data(2, &[10_000, expected_out + 1]),
+        pool.swap_accounts(true),
+    );
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_error(AmmError::SlippageExceeded))],
+    );
+
+    let ix = pool.instruction(
+        ix_data(2, &[10_000, expected_out]),
+        pool.swap_accounts(true),
+    );
+    let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+    let user_token_b = result.get_account(&pool.user_token_b).unwrap();
+    assert_eq!(token_amount(user_token_b), pool.user_balance + expected_out);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 109_900);
+    assert_eq!(pool_state.reserve_b, 100_000 - expected_out);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use amm_pinocchio::{
+    error::AmmError,
+    helper::{calculate_swap_input, calculate_swap_output},
+};
+use common::{TestPool, amm_error, ix_data, token_amount};
+use mollusk_svm::result::Check;
+
+#[test]
+fn test_swap_exact_out_success() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let ix = pool.instruction(ix_data(5, &[9_000, 10_000]), pool.swap_accounts(true));
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    let user_token_a = result.get_account(&pool.user_token_a).unwrap();
+    let user_token_b = result.get_account(&pool.user_token_b).unwrap();
+    assert_eq!(token_amount(user_token_a), 1_000_000 - 9_921);
+    assert_eq!(token_amount(user_token_b), 1_000_000 + 9_000);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 100_000 + 9_921);
+    assert_eq!(pool_state.reserve_b, 100_000 - 9_000);
+}
+
+#[test]
+fn test_swap_exact_out_slippage_exceeded() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let ix = pool.instruction(ix_data(5, &[9_000, 9_920]), pool.swap_accounts(true));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::SlippageExceeded))],
+    );
+}
+
+#[test]
+fn test_swap_exact_out_exceeds_reserve() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let ix = pool.instruction(ix_data(5, &[100_000, u64::MAX]), pool.swap_accounts(false));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InsufficientLiquidity))],
+    );
+}
+
+#[test]
+fn test_swap_input_is_minimal_and_covers_output() {
+    for (reserve_in, reserve_out, fee_rate) in [
+        (100_000, 100_000, 30),
+        (1_000_000, 3_333, 0),
+        (7_777, 9_999_999, 100),
+        (50_000, 50_000, 9_999),
+    ] {
+        for amount_out in (1..reserve_out).step_by((reserve_out / 97).max(1) as usize) {
+            let (amount_in, _) =
+                calculate_swap_input(amount_out, reserve_in, reserve_out, fee_rate).unwrap();
+
+            let (paid, _) =
+                calculate_swap_output(amount_in, reserve_in, reserve_out, fee_rate).unwrap();
+            assert!(paid >= amount_out);
+
+            let (short, _) =
+                calculate_swap_output(amount_in - 1, reserve_in, reserve_out, fee_rate).unwrap();
+            assert!(short < amount_out);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use amm_pinocchio::error::AmmError;
+use common::{TestPool, amm_error, set_account, token_account, token_amount};
+use mollusk_svm::{Mollusk, result::Check};
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey};
+
+fn sync_accounts(pool: &TestPool) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(pool.pool, false),
+        AccountMeta::new_readonly(pool.lp_mint, false),
+        AccountMeta::new_readonly(pool.vault_a, false),
+        AccountMeta::new_readonly(pool.vault_b, false),
+    ]
+}
+
+fn skim_accounts(pool: &TestPool) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(pool.pool, false),
+        AccountMeta::new(pool.vault_a, false),
+        AccountMeta::new(pool.vault_b, false),
+        AccountMeta::new(pool.user_token_a, false),
+        AccountMeta::new(pool.user_token_b, false),
+        AccountMeta::new_readonly(pool.token_a, false),
+        AccountMeta::new_readonly(pool.token_b, false),
+        AccountMeta::new_readonly(pool.token_program_a, false),
+        AccountMeta::new_readonly(pool.token_program_b, false),
+    ]
+}
+
+/// Pool accounts with `donation_a` sent straight to vault A and the protocol
+/// fees of side B held in vault B.
+fn accounts_with_donation(
+    mollusk: &Mollusk,
+    pool: &TestPool,
+    donation_a: u64,
+) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk);
+    set_account(
+        &mut accounts,
+        pool.vault_a,
+        token_account(
+            mollusk,
+            pool.token_a,
+            pool.pool,
+            pool.state.reserve_a + donation_a,
+        ),
+    );
+    set_account(
+        &mut accounts,
+        pool.vault_b,
+        token_account(
+            mollusk,
+            pool.token_b,
+            pool.pool,
+            pool.state.reserve_b + pool.state.protocol_fees_b,
+        ),
+    );
+    accounts
+}
+
+#[test]
+fn test_sync_success() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.protocol_fees_b = 70;
+
+    let ix = pool.instruction(vec![11], sync_accounts(&pool));
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts_with_donation(&mollusk, &pool, 500),
+        &[Check::success()],
+    );
+
+    // Protocol fees stay out of the reserves.
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 100_500);
+    assert_eq!(pool_state.reserve_b, 100_000);
+    assert_eq!(pool_state.protocol_fees_b, 70);
+}
+
+#[test]
+fn test_sync_empty_pool() {
+    // A donation to a fresh pool cannot be synced into its reserves.
+    let (mollusk, pool) = TestPool::new(0, 0, 0);
+
+    let ix = pool.instruction(vec![11], sync_accounts(&pool));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts_with_donation(&mollusk, &pool, 1),
+        &[Check::err(amm_error(AmmError::EmptyPool))],
+    );
+}
+
+#[test]
+fn test_sync_during_flash_loan() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.flash_loan_a = 50_000;
+
+    let ix = pool.instruction(vec![11], sync_accounts(&pool));
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::FlashLoanActive))],
+    );
+}
+
+#[test]
+fn test_skim_success() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.protocol_fees_b = 70;
+
+    let ix = pool.instruction(vec![12], skim_accounts(&pool));
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts_with_donation(&mollusk, &pool, 500),
+        &[Check::success()],
+    );
+
+    let to_token_a = result.get_account(&pool.user_token_a).unwrap();
+    let to_token_b = result.get_account(&pool.user_token_b).unwrap();
+    assert_eq!(token_amount(to_token_a), pool.user_balance + 500);
+    assert_eq!(token_amount(to_token_b), pool.user_balance);
+
+    let vault_a = result.get_account(&pool.vault_a).unwrap();
+    let vault_b = result.get_account(&pool.vault_b).unwrap();
+    assert_eq!(token_amount(vault_a), 100_000);
+    assert_eq!(token_amount(vault_b), 100_070);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 100_000);
+    assert_eq!(pool_state.reserve_b, 100_000);
+}
+
+#[test]
+fn test_skim_invalid_vault() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let mut accounts = skim_accounts(&pool);
+    accounts.swap(1, 2);
+    let ix = pool.instruction(vec![12], accounts);
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InvalidVault))],
+    );
+}
@@ -0,0 +1,140 @@
+use amm_pinocchio::transfer_hook::{
+    EXECUTE_DISCRIMINATOR, EXTRA_ACCOUNT_METAS_SEED, HookAccountMeta,
+    resolve_transfer_hook_accounts,
+};
+use pinocchio::program_error::ProgramError;
+use solana_sdk::pubkey::Pubkey;
+
+fn transfer_hook_mint(hook_program: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0; 165];
+    data.push(1); // mint account type
+    data.extend_from_slice(&[14, 0, 64, 0]);
+    data.extend_from_slice(&[0; 32]); // authority
+    data.extend_from_slice(hook_program.as_ref());
+    data
+}
+
+fn extra_account_meta(discriminator: u8, config: &[u8], is_writable: bool) -> Vec<u8> {
+    let mut meta = vec![discriminator];
+    meta.extend_from_slice(config);
+    meta.resize(33, 0);
+    meta.push(0); // is_signer
+    meta.push(is_writable as u8);
+    meta
+}
+
+fn validation_account(metas: &[Vec<u8>]) -> Vec<u8> {
+    let mut value = (metas.len() as u32).to_le_bytes().to_vec();
+    for meta in metas {
+        value.extend_from_slice(meta);
+    }
+
+    let mut data = EXECUTE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    data.extend_from_slice(&value);
+    data
+}
+
+fn meta(pubkey: Pubkey, is_writable: bool) -> HookAccountMeta {
+    HookAccountMeta {
+        pubkey: pubkey.to_bytes(),
+        is_signer: false,
+        is_writable,
+    }
+}
+
+#[test]
+fn test_resolve_transfer_hook_accounts() {
+    let hook_program = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let source = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+    let destination_owner = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let external_program = Pubkey::new_unique();
+    let amount = 10_000u64;
+
+    let (validation, _) =
+        Pubkey::find_program_address(&[EXTRA_ACCOUNT_METAS_SEED, mint.as_ref()], &hook_program);
+
+    // Literal seed "counter" and the mint key.
+    let mut counter_seeds = vec![1, 7];
+    counter_seeds.extend_from_slice(b"counter");
+    counter_seeds.extend_from_slice(&[3, 1]);
+
+    // Source key, the amount from the `Execute` data and the destination owner.
+    let external_seeds = [3, 0, 2, 8, 8, 4, 2, 32, 32];
+
+    let validation_data = validation_account(&[
+        extra_account_meta(0, external_program.as_ref(), false),
+        extra_account_meta(1, &counter_seeds, true),
+        extra_account_meta(128 + 5, &external_seeds, false),
+        extra_account_meta(2, &[2, 2, 32], false),
+    ]);
+
+    let mut destination_data = vec![0; 165];
+    destination_data[32..64].copy_from_slice(destination_owner.as_ref());
+
+    let accounts = resolve_transfer_hook_accounts(
+        &mint.to_bytes(),
+        &transfer_hook_mint(&hook_program),
+        &source.to_bytes(),
+        &destination.to_bytes(),
+        &authority.to_bytes(),
+        amount,
+        |key| {
+            if *key == validation.to_bytes() {
+                Some(validation_data.clone())
+            } else if *key == destination.to_bytes() {
+                Some(destination_data.clone())
+            } else {
+                None
+            }
+        },
+    )
+    .unwrap();
+
+    let (counter, _) = Pubkey::find_program_address(&[b"counter", mint.as_ref()], &hook_program);
+    let (external, _) = Pubkey::find_program_address(
+        &[
+            source.as_ref(),
+            &amount.to_le_bytes(),
+            destination_owner.as_ref(),
+        ],
+        &external_program,
+    );
+
+    assert_eq!(
+        accounts,
+        vec![
+            meta(external_program, false),
+            meta(counter, true),
+            meta(external, false),
+            meta(destination_owner, false),
+            meta(hook_program, false),
+            meta(validation, false),
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_transfer_hook_accounts_without_hook() {
+    let mint_data = vec![0; 82];
+    let key = Pubkey::new_unique().to_bytes();
+
+    let accounts =
+        resolve_transfer_hook_accounts(&key, &mint_data, &key, &key, &key, 1, |_| None).unwrap();
+
+    assert!(accounts.is_empty());
+}
+
+#[test]
+fn test_resolve_transfer_hook_accounts_missing_validation_account() {
+    let mint_data = transfer_hook_mint(&Pubkey::new_unique());
+    let key = Pubkey::new_unique().to_bytes();
+
+    assert_eq!(
+        resolve_transfer_hook_accounts(&key, &mint_data, &key, &key, &key, 1, |_| None),
+        Err(ProgramError::UninitializedAccount)
+    );
+}
@@ -1,8 +1,18 @@
+mod common;
+
 use amm_pinocchio::{
-    constants::{LP_MINT_SEED, POOL_SEED},
+    constants::{
+        DEPOSITS_DISABLED, LP_MINT_SEED, POOL_SEED, SWAPS_DISABLED, VAULT_SEED,
+        WITHDRAWALS_DISABLED,
+    },
+    curve::CURVE_CONSTANT_PRODUCT,
+    error::AmmError,
+    instructions::deadline::DEADLINE_SLOT,
     states::Pool,
 };
-use mollusk_svm::{Mollusk, program};
+use bytemuck::Zeroable;
+use common::{TestPool, amm_error, ix_data, token_amount};
+use mollusk_svm::{Mollusk, program, result::Check};
 use solana_sdk::{
     account::{Account, WritableAccount},
     program_option::COption,
@@ -69,14 +79,22 @@ fn test_withdraw_success() {
     )
     .unwrap();
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
-        &[POOL_SEED.as_bytes(), token_a.as_ref(), token_b.as_ref()],
+        &[
+            POOL_SEED.as_bytes(),
+            token_a.as_ref(),
+            token_b.as_ref(),
+            &[CURVE_CONSTANT_PRODUCT],
+        ],
         &program_id,
     );
 
     let (lp_mint, lp_mint_bump) =
         Pubkey::find_program_address(&[LP_MINT_SEED.as_bytes(), pool_pda.as_ref()], &program_id);
 
-    let vault_a = Pubkey::new_from_array([0x05; 32]);
+    let (vault_a, vault_a_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_a.as_ref()],
+        &program_id,
+    );
     let mut vault_a_acc = Account::new(
         mollusk
             .sysvars
@@ -101,7 +119,10 @@ fn test_withdraw_success() {
     )
     .unwrap();
 
-    let vault_b = Pubkey::new_from_array([0x09; 32]);
+    let (vault_b, vault_b_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED.as_bytes(), pool_pda.as_ref(), token_b.as_ref()],
+        &program_id,
+    );
     let mut vault_b_acc = Account::new(
         mollusk
             .sysvars
@@ -141,7 +162,11 @@ fn test_withdraw_success() {
         fee_rate: 30,
         bump: pool_bump,
         lp_mint_bump,
-        _padding: [0; 4],
+        vault_a_bump,
+        vault_b_bump,
+        token_program_a: token_program.to_bytes(),
+        token_program_b: token_program.to_bytes(),
+        ..Zeroable::zeroed()
     };
     pool_account
         .data_as_mut_slice()
@@ -263,6 +288,10 @@ fn test_withdraw_success() {
             solana_sdk::instruction::AccountMeta::new(user_token_a, false),
             solana_sdk::instruction::AccountMeta::new(user_token_b, false),
             solana_sdk::instruction::AccountMeta::new_readonly(token_program, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(token_a, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(token_b, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(token_program, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(token_program, false),
         ],
         data,
     };
@@ -278,6 +307,8 @@ fn test_withdraw_success() {
             (user_lp_token, user_lp_token_account),
             (user_token_a, user_token_a_account),
             (user_token_b, user_token_b_account),
+            (token_a, mint_a_account),
+            (token_b, mint_b_account),
             (token_program, token_account),
         ],
         &[mollusk_svm::result::Check::success()],
@@ -285,3 +316,113 @@ fn test_withdraw_success() {
 
     assert!(!result.program_result.is_err());
 }
+
+#[test]
+fn test_withdraw_insufficient_lp_balance() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.user_lp_balance = 5_000;
+
+    let ix = pool.instruction(ix_data(3, &[10_000, 0, 0]), pool.withdraw_accounts());
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InsufficientLpBalance))],
+    );
+}
+
+#[test]
+fn test_withdraw_below_minimum_liquidity() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    // Would leave 500 LP tokens, below the locked minimum liquidity.
+    let ix = pool.instruction(ix_data(3, &[99_500, 0, 0]), pool.withdraw_accounts());
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InsufficientLiquidity))],
+    );
+}
+
+#[test]
+fn test_withdraw_disabled() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.status = WITHDRAWALS_DISABLED;
+
+    let ix = pool.instruction(ix_data(3, &[10_000, 0, 0]), pool.withdraw_accounts());
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::WithdrawalsDisabled))],
+    );
+}
+
+#[test]
+fn test_withdraw_only_pool() {
+    let (mollusk, mut pool) = TestPool::new(100_000, 100_000, 100_000);
+    pool.state.status = SWAPS_DISABLED | DEPOSITS_DISABLED;
+
+    let ix = pool.instruction(ix_data(3, &[10_000, 0, 0]), pool.withdraw_accounts());
+
+    mollusk.process_and_validate_instruction(&ix, &pool.accounts(&mollusk), &[Check::success()]);
+}
+
+#[test]
+fn test_withdraw_slippage_exceeded() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let ix = pool.instruction(
+        ix_data(3, &[10_000, 10_000, 10_001]),
+        pool.withdraw_accounts(),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::SlippageExceeded))],
+    );
+}
+
+#[test]
+fn test_withdraw_deadline_exceeded() {
+    let (mut mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+    mollusk.warp_to_slot(10);
+
+    let mut data = ix_data(3, &[10_000, 0, 0]);
+    data.push(DEADLINE_SLOT);
+    data.extend_from_slice(&9u64.to_le_bytes());
+
+    mollusk.process_and_validate_instruction(
+        &pool.instruction(data, pool.withdraw_accounts()),
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::DeadlineExceeded))],
+    );
+}
+
+#[test]
+fn test_withdraw_stable_swap() {
+    let (mollusk, pool) = TestPool::stable_swap(100_000, 150_000, 100_000, 100);
+
+    let ix = pool.instruction(
+        ix_data(3, &[10_000, 10_000, 15_000]),
+        pool.withdraw_accounts(),
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    // Withdrawals pay out a proportional share of each reserve.
+    let user_token_a = result.get_account(&pool.user_token_a).unwrap();
+    assert_eq!(token_amount(user_token_a), pool.user_balance + 10_000);
+    let user_token_b = result.get_account(&pool.user_token_b).unwrap();
+    assert_eq!(token_amount(user_token_b), pool.user_balance + 15_000);
+
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 90_000);
+    assert_eq!(pool_state.reserve_b, 135_000);
+}
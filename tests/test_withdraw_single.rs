@@ -0,0 +1,74 @@
+use mollusk_svm::result::Check;
+
+mod common;
+
+use amm_pinocchio::error::AmmError;
+use amm_pinocchio::helper::calculate_swap_output;
+use common::{TestPool, amm_error, ix_data, token_amount};
+
+#[test]
+fn test_withdraw_single_success() {
+    let (mollusk, pool) = TestPool::new(100_000, 200_000, 100_000);
+
+    // 10% of the pool: 10_000 A directly, plus 20_000 B swapped into A against
+    // the remaining 90_000 A / 180_000 B.
+    let (swap_out, _) = calculate_swap_output(20_000, 180_000, 90_000, 30).unwrap();
+    let amount_out = 10_000 + swap_out;
+
+    let ix = pool.instruction(
+        ix_data(14, &[10_000, amount_out]),
+        pool.withdraw_single_accounts(pool.side_a()),
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::success()],
+    );
+
+    let user_token_a = result.get_account(&pool.user_token_a).unwrap();
+    assert_eq!(token_amount(user_token_a), pool.user_balance + amount_out);
+    let user_token_b = result.get_account(&pool.user_token_b).unwrap();
+    assert_eq!(token_amount(user_token_b), pool.user_balance);
+    let user_lp_token = result.get_account(&pool.user_lp_token).unwrap();
+    assert_eq!(token_amount(user_lp_token), pool.user_lp_balance - 10_000);
+
+    // Only token A leaves the pool; the B share stays in reserve B.
+    let pool_state = TestPool::pool_state(result.get_account(&pool.pool).unwrap());
+    assert_eq!(pool_state.reserve_a, 100_000 - amount_out);
+    assert_eq!(pool_state.reserve_b, 200_000);
+}
+
+#[test]
+fn test_withdraw_single_slippage_exceeded() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    // The swapped half pays a fee and price impact, so 10% of the pool is
+    // worth less than 20_000 B.
+    let ix = pool.instruction(
+        ix_data(14, &[10_000, 20_000]),
+        pool.withdraw_single_accounts(pool.side_b()),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::SlippageExceeded))],
+    );
+}
+
+#[test]
+fn test_withdraw_single_below_minimum_liquidity() {
+    let (mollusk, pool) = TestPool::new(100_000, 100_000, 100_000);
+
+    let ix = pool.instruction(
+        ix_data(14, &[99_500, 0]),
+        pool.withdraw_single_accounts(pool.side_a()),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &pool.accounts(&mollusk),
+        &[Check::err(amm_error(AmmError::InsufficientLiquidity))],
+    );
+}